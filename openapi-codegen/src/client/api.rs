@@ -1,70 +1,1432 @@
 use crate::RustType;
+use openapiv3::Header;
+use openapiv3::MediaType;
 use openapiv3::ReferenceOr;
 use openapiv3::RequestBody;
-use serde_derive::Serialize;
+use openapiv3::Response as ResponseV3;
+use openapiv3::{
+    ParameterSchemaOrContent, PathStyle, QueryStyle, Schema, SchemaVariant, StringFormat,
+    VariantOrUnknownOrEmpty,
+};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+use crate::client::model::DataType;
 
 use crate::{RustPascalIdentifier, RustSnakeIdentifier};
 
 #[derive(Debug, Serialize)]
 pub struct Api {
+    /// The group, such as a tag or a path segment
+    pub api_id: String,
     pub snake_id: RustSnakeIdentifier,
     pub pascal_id: RustPascalIdentifier,
     pub methods: Vec<Method>, // Will be vec<Method>
     pub tests: bool,
+    /// The modules within `apis` holding the client, for a nested group
+    pub module: Vec<RustSnakeIdentifier>,
+    /// The path from `apis` down to the client's module, ending in `::` when there is one
+    pub path: String,
+    /// The name of the root `Client` method giving this client
+    pub accessor: RustSnakeIdentifier,
+    /// What the client's methods call, for its imports
+    pub runtime: Runtime,
+}
+
+impl Api {
+    /// A client for a group, which is split at each `/` into nested modules when `nested`
+    pub fn new(group: &str, nested: bool, tests: bool) -> Self {
+        let mut segments = if nested {
+            group
+                .split('/')
+                .filter(|segment| !segment.is_empty())
+                .map(str::to_owned)
+                .collect::<Vec<String>>()
+        } else {
+            vec![]
+        };
+        let name = segments.pop().unwrap_or_else(|| group.to_owned());
+        let module = segments
+            .into_iter()
+            .map(RustSnakeIdentifier::from)
+            .collect::<Vec<RustSnakeIdentifier>>();
+
+        let accessor: RustSnakeIdentifier = group.to_owned().into();
+        // The root client has methods of its own
+        let accessor = match accessor.to_string().as_str() {
+            "new" | "configuration" | "default" => format!("{}_api", accessor).into(),
+            _ => accessor,
+        };
+
+        Api {
+            api_id: group.to_owned(),
+            snake_id: name.clone().into(),
+            pascal_id: name.into(),
+            methods: vec![],
+            tests,
+            path: module
+                .iter()
+                .map(|segment| format!("r#{}::", segment))
+                .collect(),
+            module,
+            accessor,
+            runtime: Runtime::default(),
+        }
+    }
+}
+
+/// The parts of the bundled runtime which the generated code calls, so that only those are
+/// written. The templates test each part as `runtime.{part}`.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct Runtime(BTreeMap<&'static str, bool>);
+
+impl Runtime {
+    pub fn uses(&mut self, part: &'static str) {
+        self.0.insert(part, true);
+    }
+
+    pub fn has(&self, part: &str) -> bool {
+        self.0.contains_key(part)
+    }
+
+    fn uses_if(&mut self, part: &'static str, used: bool) {
+        if used {
+            self.uses(part);
+        }
+    }
+
+    fn has_any(&self, parts: &[&str]) -> bool {
+        parts.iter().any(|part| self.has(part))
+    }
+
+    /// Adds the parts `other` uses
+    pub fn merge(&mut self, other: &Runtime) {
+        self.0
+            .extend(other.0.iter().map(|(part, used)| (*part, *used)));
+    }
+
+    /// Adds the parts which those already used call in turn
+    pub fn complete(&mut self) {
+        self.uses_if(
+            "parse_text",
+            self.has_any(&["response_headers", "links", "pagination"]),
+        );
+        self.uses_if("pairs", self.has_any(&["cookie", "form"]));
+        self.uses_if("split", self.has_any(&["query", "pairs"]));
+        self.uses_if("expand", self.has_any(&["path", "header"]));
+        self.uses_if("scalar", self.has_any(&["split", "expand"]));
+        self.uses_if("styles", self.has_any(&["path", "query", "pairs"]));
+        self.uses_if("value", self.has_any(&["scalar", "parse_text"]));
+        self.uses_if(
+            "binary_module",
+            self.has_any(&["binary", "byte_stream", "read_bytes"]),
+        );
+        self.uses_if(
+            "page_serialize",
+            self.has_any(&["page_items", "body_cursor", "page_limit"]),
+        );
+        self.uses_if("page_value", self.has_any(&["page_items", "body_cursor"]));
+    }
 }
 
 #[derive(Debug, Serialize)]
 pub struct Method {
     pub snake_id: RustSnakeIdentifier,
+    pub pascal_id: RustPascalIdentifier,
     pub path: String,
     pub http_method: String,
     pub path_parameters: Vec<Parameter>,
     pub query_parameters: Vec<Parameter>,
-    pub body: Option<Parameter>,
+    pub header_parameters: Vec<Parameter>,
+    pub cookie_parameters: Vec<Parameter>,
+    pub body: Option<Body>,
     pub returns: Option<RustType>,
+    /// Every documented status, exact codes ahead of the `2XX` style ranges containing them
+    pub responses: Vec<Response>,
+    /// The `default` response, covering every undocumented status
+    pub default: Option<Response>,
+    /// Has more than one success status, so returns a `{Operation}Response` enum
+    pub success_enum: bool,
+    /// Documents at least one success status, otherwise any 2xx is taken as success
+    pub documented_success: bool,
+    /// The lifetime of the success enum when one of its variants borrows from the buffer
+    pub response_generics: String,
+    /// Returns the response headers rather than a body
+    pub head: bool,
+    /// Deserializes the response into a caller-provided buffer, borrowing from it
+    pub borrows: bool,
+    /// The headers declared on the success responses, returned alongside the body
+    pub response_headers: Vec<ResponseHeader>,
+    /// Takes its parameters as an `{Operation}Params` struct, rather than one by one
+    pub params: bool,
+    pub params_fields: Vec<Parameter>,
+    /// Every parameter is optional, so the params struct has a `Default`
+    pub params_default: bool,
+    /// The lifetime of the params struct when one of its fields borrows
+    pub params_generics: String,
+    /// Walks the pages of a list operation, as described by its `x-pagination` extension
+    pub pagination: Option<Pagination>,
+    /// Has an internal `{operation}_with_headers` variant also returning the raw response
+    /// headers, for pagination to read its cursor from
+    pub page_headers: bool,
+    /// Follow the response links which lead to this operation
+    pub links: Vec<Link>,
+    /// The alternative security requirements, as a `&[&[&str]]` of scheme names
+    pub security: String,
+    /// The `Server` constructor for the default server of an operation with its own `servers`
+    pub server: Option<String>,
+    /// The `use` of this operation's types from the client defining them, when another client
+    /// holds the operation too
+    pub shared_types: Option<String>,
+}
+
+impl Method {
+    /// Sorts the responses and works out what the operation returns on success
+    pub fn with_responses(
+        mut self,
+        mut responses: Vec<Response>,
+        default: Option<Response>,
+    ) -> Self {
+        if self.head {
+            for response in responses.iter_mut() {
+                response.r#type = None;
+            }
+        }
+        // A stable sort keeps the spec's order within exact codes and within ranges
+        responses.sort_by_key(|response| response.range);
+        let successes = responses
+            .iter()
+            .filter(|response| response.success)
+            .collect::<Vec<&Response>>();
+
+        self.documented_success = !successes.is_empty();
+        if !self.head {
+            for header in successes
+                .iter()
+                .flat_map(|response| response.headers.iter())
+            {
+                if !self
+                    .response_headers
+                    .iter()
+                    .any(|existing| existing.api_id.eq_ignore_ascii_case(&header.api_id))
+                {
+                    self.response_headers.push(header.clone());
+                }
+            }
+            // A header can only be relied on if every success response requires it
+            for header in self.response_headers.iter_mut() {
+                header.required = successes.iter().all(|response| {
+                    response.headers.iter().any(|other| {
+                        other.required && other.api_id.eq_ignore_ascii_case(&header.api_id)
+                    })
+                });
+            }
+        }
+        self.success_enum = !self.head && successes.len() > 1;
+        self.returns = if self.head {
+            None
+        } else if self.success_enum {
+            Some(RustType(format!("{}Response", self.pascal_id)))
+        } else {
+            successes
+                .first()
+                .and_then(|response| response.r#type.clone())
+        };
+        self.responses = responses;
+        self.default = default;
+        self
+    }
+
+    /// Gathers the parameters into an `{Operation}Params` struct, if there are any
+    pub fn with_params_struct(&mut self) {
+        let mut fields = self
+            .path_parameters
+            .iter()
+            .chain(self.query_parameters.iter())
+            .chain(self.header_parameters.iter())
+            .chain(self.cookie_parameters.iter())
+            .cloned()
+            .collect::<Vec<Parameter>>();
+        // A struct cannot hold the elided lifetimes of zero-copy parameters, so names its own
+        if fields.iter().any(|field| field.r#type.0.contains("'_")) {
+            self.params_generics = "<'p>".to_string();
+            for field in fields.iter_mut() {
+                field.r#type = RustType(field.r#type.0.replace("'_", "'p"));
+            }
+        }
+        self.params = !fields.is_empty();
+        self.params_default = fields.iter().all(|field| !field.required);
+        self.params_fields = fields;
+    }
+
+    /// Adds a `{operation}_pages` iterator following the pages the extension describes
+    pub fn paginate(
+        &mut self,
+        extension: PaginationExtension,
+        models: &[DataType],
+    ) -> Result<(), failure::Error> {
+        let returns = match self.returns.as_ref() {
+            Some(returns) if !self.success_enum && !self.borrows => returns.0.clone(),
+            _ => {
+                return Err(failure::format_err!(
+                    "{} can only be paginated with a single, owned success body",
+                    self.snake_id
+                ))
+            }
+        };
+        if self.body.is_some() || self.parameter_types().any(|r#type| r#type.0.contains('\'')) {
+            return Err(failure::format_err!(
+                "{} can only be paginated without a body or borrowed parameters",
+                self.snake_id
+            ));
+        }
+        let parameter = match self
+            .path_parameters
+            .iter()
+            .chain(self.query_parameters.iter())
+            .chain(self.header_parameters.iter())
+            .chain(self.cookie_parameters.iter())
+            .find(|parameter| parameter.api_id == extension.param)
+        {
+            Some(parameter) => parameter.clone(),
+            None => {
+                return Err(failure::format_err!(
+                    "{} has no parameter {} to paginate with",
+                    self.snake_id,
+                    extension.param
+                ))
+            }
+        };
+        // The first page is fetched without a cursor, and a params struct cannot unset one
+        let offset = extension.strategy == PaginationStrategy::Offset;
+        if parameter.required && (!offset || self.params) {
+            return Err(failure::format_err!(
+                "{} must be optional to paginate {}",
+                parameter.api_id,
+                self.snake_id
+            ));
+        }
+
+        let header = match extension.strategy {
+            PaginationStrategy::Header => match extension.header.as_ref() {
+                Some(header) => Some(header.clone()),
+                None => return Err(failure::format_err!("the header strategy needs a header")),
+            },
+            _ => None,
+        };
+        let body_cursor = match extension.strategy {
+            PaginationStrategy::Body => match extension.cursor.as_ref() {
+                Some(pointer) => Some(pointer.clone()),
+                None => return Err(failure::format_err!("the body strategy needs a cursor")),
+            },
+            _ => None,
+        };
+        let limit = match (offset, &extension.limit) {
+            (true, Some(limit)) => match self
+                .query_parameters
+                .iter()
+                .find(|parameter| parameter.api_id == *limit)
+            {
+                Some(limit) => Some(limit.snake_id.clone()),
+                None => {
+                    return Err(failure::format_err!(
+                        "{} has no parameter {} to limit pages with",
+                        self.snake_id,
+                        limit
+                    ))
+                }
+            },
+            _ => None,
+        };
+
+        let item = match extension.items.as_ref() {
+            Some(pointer) => models
+                .iter()
+                .filter_map(|model| match model {
+                    DataType::Struct(_struct) if _struct.pascal_id.to_string() == returns => {
+                        Some(_struct)
+                    }
+                    _ => None,
+                })
+                .flat_map(|_struct| {
+                    _struct
+                        .optional_fields
+                        .iter()
+                        .chain(_struct.required_fields.iter())
+                })
+                .find(|field| field.pointer == *pointer)
+                .and_then(|field| vec_item(&field.r#type.0)),
+            None => vec_item(&returns).or_else(|| {
+                models.iter().find_map(|model| match model {
+                    DataType::NewType(newtype) if newtype.pascal_id.to_string() == returns => {
+                        newtype.item.as_ref().map(|item| item.0.clone())
+                    }
+                    _ => None,
+                })
+            }),
+        };
+        let item = match item {
+            Some(item) => item,
+            None => {
+                return Err(failure::format_err!(
+                    "{} does not return a list at {}",
+                    self.snake_id,
+                    extension.items.as_ref().map_or("its root", String::as_str)
+                ))
+            }
+        };
+
+        let description = match extension.strategy {
+            PaginationStrategy::Header => format!(
+                "following the cursor in the `{}` header",
+                header.as_ref().map_or("", String::as_str)
+            ),
+            PaginationStrategy::Link => {
+                "following the `rel=\"next\"` target of the `Link` header".to_string()
+            }
+            PaginationStrategy::Body => format!(
+                "following the cursor at `{}` in the body",
+                body_cursor.as_ref().map_or("", String::as_str)
+            ),
+            PaginationStrategy::Offset => {
+                format!("moving `{}` on by the items of each page", parameter.api_id)
+            }
+        };
+        // The cursor headers are read from an internal variant of the operation, so it keeps
+        // returning only the headers it declares
+        self.page_headers = header.is_some() || extension.strategy == PaginationStrategy::Link;
+
+        let parameters = self
+            .path_parameters
+            .iter()
+            .chain(self.query_parameters.iter())
+            .chain(self.header_parameters.iter())
+            .chain(self.cookie_parameters.iter());
+        self.pagination = Some(Pagination {
+            snake_id: format!("{}_pages", self.snake_id).into(),
+            description,
+            item,
+            arguments: if self.params {
+                vec![]
+            } else {
+                parameters
+                    .clone()
+                    .filter(|argument| argument.api_id != parameter.api_id)
+                    .cloned()
+                    .collect()
+            },
+            call: if self.params {
+                vec![]
+            } else {
+                parameters
+                    .map(|argument| PageArgument {
+                        snake_id: argument.snake_id.clone(),
+                        paginates: argument.api_id == parameter.api_id,
+                    })
+                    .collect()
+            },
+            cursor_type: if offset { "u64" } else { "String" },
+            param: parameter,
+            items: extension.items,
+            header,
+            link: extension.strategy == PaginationStrategy::Link,
+            body_cursor,
+            offset,
+            limit,
+        });
+        Ok(())
+    }
+
+    /// Adds a `follow_{link}` method calling this operation with the parameters a response link
+    /// fills in. Those it leaves out are taken as arguments when required, or left unset.
+    pub fn add_link(
+        &mut self,
+        name: &str,
+        source: &RustSnakeIdentifier,
+        from: &RustType,
+        values: &[(String, LinkValue)],
+    ) {
+        let mut snake_id: RustSnakeIdentifier = format!("follow_{}", name).into();
+        if let Some(existing) = self.links.iter().find(|link| link.snake_id == snake_id) {
+            if existing.from == from.0 {
+                return;
+            }
+            snake_id = format!("follow_{}_from_{}", name, source).into();
+        }
+
+        let parameters = [
+            ("path", &self.path_parameters),
+            ("query", &self.query_parameters),
+            ("header", &self.header_parameters),
+            ("cookie", &self.cookie_parameters),
+        ]
+        .iter()
+        .flat_map(|(location, parameters)| {
+            parameters.iter().map(move |parameter| {
+                // A link may qualify the name with its location, as in `path.id`
+                let value = values
+                    .iter()
+                    .find(|(name, _)| {
+                        *name == parameter.api_id
+                            || *name == format!("{}.{}", location, parameter.api_id)
+                    })
+                    .map(|(_, value)| value.expression(parameter.required));
+                (parameter, value)
+            })
+        })
+        .collect::<Vec<(&Parameter, Option<String>)>>();
+
+        let mut setup = vec![];
+        let mut call = if self.params {
+            let required = parameters
+                .iter()
+                .filter(|(parameter, _)| parameter.required)
+                .map(|(parameter, value)| match value {
+                    Some(value) => value.to_owned(),
+                    None => format!("r#{}", parameter.snake_id),
+                })
+                .collect::<Vec<String>>();
+            let optional = parameters
+                .iter()
+                .filter(|(parameter, _)| !parameter.required)
+                .filter_map(|(parameter, value)| value.as_ref().map(|value| (parameter, value)))
+                .map(|(parameter, value)| {
+                    format!(
+                        "if let Some(value) = {} {{ params = params.with_{}(value); }}",
+                        value, parameter.snake_id
+                    )
+                })
+                .collect::<Vec<String>>();
+            setup.push(format!(
+                "let {}params = {}Params::new({});",
+                if optional.is_empty() { "" } else { "mut " },
+                self.pascal_id,
+                required.join(", ")
+            ));
+            setup.extend(optional);
+            vec!["params".to_string()]
+        } else {
+            parameters
+                .iter()
+                .map(|(parameter, value)| match (value, parameter.required) {
+                    (Some(value), _) => value.to_owned(),
+                    (None, true) => format!("r#{}", parameter.snake_id),
+                    (None, false) => "None".to_string(),
+                })
+                .collect()
+        };
+        if let Some(body) = self.body.as_ref() {
+            call.push(format!("r#{}", body.snake_id));
+        }
+        if self.borrows {
+            call.push("buffer".to_string());
+        }
+
+        self.links.push(Link {
+            snake_id,
+            api_id: name.to_owned(),
+            source: source.to_string(),
+            from: from.0.clone(),
+            arguments: parameters
+                .iter()
+                .filter(|(parameter, value)| parameter.required && value.is_none())
+                .map(|(parameter, _)| (*parameter).clone())
+                .collect(),
+            setup,
+            call: call.join(", "),
+        });
+    }
+
+    /// The body type of the success response documented under `status`, for links to borrow
+    pub fn response_type(&self, status: &str) -> Option<RustType> {
+        let pattern = if status.len() == 3 && status[1..].eq_ignore_ascii_case("XX") {
+            format!("{}00..={}99", &status[..1], &status[..1])
+        } else {
+            status.to_owned()
+        };
+        self.responses
+            .iter()
+            .find(|response| response.success && response.pattern == pattern)
+            .and_then(|response| response.r#type.as_ref())
+            .map(|r#type| RustType(r#type.0.replace("'b", "'_")))
+    }
+
+    /// The client methods generated for this operation, such as `{operation}_pages`
+    pub fn method_names(&self) -> Vec<String> {
+        let mut names = vec![self.snake_id.to_string()];
+        if self.page_headers {
+            names.push(format!("{}_with_headers", self.snake_id));
+        }
+        names.extend(
+            self.pagination
+                .iter()
+                .map(|pagination| pagination.snake_id.to_string()),
+        );
+        names.extend(self.links.iter().map(|link| link.snake_id.to_string()));
+        names
+    }
+
+    /// The types generated for this operation alone, such as `{Operation}Error`
+    pub fn type_names(&self) -> Vec<String> {
+        let mut names = vec![format!("{}Error", self.pascal_id)];
+        if self.success_enum {
+            names.push(format!("{}Response", self.pascal_id));
+        }
+        if !self.response_headers.is_empty() {
+            names.push(format!("{}Headers", self.pascal_id));
+        }
+        if self.params {
+            names.push(format!("{}Params", self.pascal_id));
+        }
+        if let Some(form) = self.body.as_ref().and_then(|body| body.form.as_ref()) {
+            names.push(form.pascal_id.to_string());
+        }
+        names
+    }
+
+    /// Each parameter along with where it is sent: `path`, `query`, `header` or `cookie`
+    pub fn parameters_mut(&mut self) -> impl Iterator<Item = (&'static str, &mut Parameter)> {
+        self.path_parameters
+            .iter_mut()
+            .map(|parameter| ("path", parameter))
+            .chain(
+                self.query_parameters
+                    .iter_mut()
+                    .map(|parameter| ("query", parameter)),
+            )
+            .chain(
+                self.header_parameters
+                    .iter_mut()
+                    .map(|parameter| ("header", parameter)),
+            )
+            .chain(
+                self.cookie_parameters
+                    .iter_mut()
+                    .map(|parameter| ("cookie", parameter)),
+            )
+    }
+
+    pub fn parameter_types(&self) -> impl Iterator<Item = &RustType> {
+        self.path_parameters
+            .iter()
+            .chain(self.query_parameters.iter())
+            .chain(self.header_parameters.iter())
+            .chain(self.cookie_parameters.iter())
+            .map(|parameter| &parameter.r#type)
+    }
+
+    /// The parts of the bundled runtime the method calls
+    pub fn runtime(&self) -> Runtime {
+        let mut runtime = Runtime::default();
+        let expressions = self
+            .responses
+            .iter()
+            .chain(self.default.iter())
+            .map(|response| &response.parse)
+            .chain(
+                self.links
+                    .iter()
+                    .flat_map(|link| link.setup.iter().chain(Some(&link.call))),
+            );
+        for expression in expressions {
+            for (call, part) in &[
+                ("_internal_binary::ByteStream::new", "byte_stream"),
+                ("_internal_binary::read_bytes", "read_bytes"),
+                ("_internal_request::json_borrowed", "json_borrowed"),
+                ("_internal_links::", "links"),
+                ("_internal_links::body_value", "body_value"),
+                ("_internal_style::parse_text", "parse_text"),
+            ] {
+                runtime.uses_if(part, expression.contains(call));
+            }
+        }
+
+        runtime.uses_if("path", !self.path_parameters.is_empty());
+        runtime.uses_if("query", !self.query_parameters.is_empty());
+        runtime.uses_if("header", !self.header_parameters.is_empty());
+        runtime.uses_if("cookie", !self.cookie_parameters.is_empty());
+        runtime.uses_if("response_headers", !self.response_headers.is_empty());
+        runtime.uses_if(
+            "required_header",
+            self.response_headers.iter().any(|header| header.required),
+        );
+        runtime.uses_if("server", self.server.is_some());
+
+        if let Some(body) = self.body.as_ref() {
+            match body.form.as_ref() {
+                Some(form) if form.urlencoded => runtime.uses("form"),
+                Some(form) => {
+                    runtime.uses("multipart");
+                    runtime.uses_if(
+                        "multipart_text",
+                        form.fields.iter().any(|field| !field.file),
+                    );
+                    runtime.uses_if("file_part", form.fields.iter().any(|field| field.file));
+                }
+                None => {
+                    runtime.uses("content_type");
+                    runtime.uses_if("text_body", body.text);
+                    runtime.uses_if("binary", body.binary);
+                }
+            }
+        }
+
+        if let Some(pagination) = self.pagination.as_ref() {
+            runtime.uses("pagination");
+            runtime.uses_if("page_items", pagination.items.is_some());
+            runtime.uses_if("header_cursor", pagination.header.is_some());
+            runtime.uses_if("link_param", pagination.link);
+            runtime.uses_if("body_cursor", pagination.body_cursor.is_some());
+            runtime.uses_if("page_cursor", !pagination.offset);
+            runtime.uses_if("offset", pagination.offset);
+            runtime.uses_if("page_limit", pagination.limit.is_some());
+        }
+
+        // The client imports what its own calls and types use, leaving out the types held by
+        // another client
+        let types = self.shared_types.is_none();
+        runtime.uses_if(
+            "style",
+            runtime.has_any(&["path", "query", "header", "cookie", "parse_text"])
+                || types && runtime.has_any(&["form", "response_headers"]),
+        );
+        runtime.uses_if("multipart_types", types && runtime.has("multipart"));
+        runtime
+    }
+
+    /// Gives every zero-copy model the lifetime it is borrowed for
+    pub fn borrow_types(&mut self, borrowing: &HashSet<String>) {
+        for parameter in self
+            .path_parameters
+            .iter_mut()
+            .chain(self.query_parameters.iter_mut())
+            .chain(self.header_parameters.iter_mut())
+            .chain(self.cookie_parameters.iter_mut())
+        {
+            parameter.r#type = parameter.r#type.with_lifetime("'_", borrowing);
+        }
+        if let Some(body) = self.body.as_mut() {
+            body.r#type = body.r#type.with_lifetime("'_", borrowing);
+            // Forms are generated structs, so cannot take an elided lifetime
+            for field in body.form.iter_mut().flat_map(|form| form.fields.iter_mut()) {
+                field.r#type = field.r#type.with_lifetime("'static", borrowing);
+            }
+        }
+        for response in self.responses.iter_mut().chain(self.default.iter_mut()) {
+            response.borrow_types(borrowing);
+            self.borrows |= response.success && response.borrows;
+        }
+        if self.success_enum && self.borrows {
+            self.response_generics = "<'b>".to_string();
+            self.returns = Some(RustType(format!("{}Response<'b>", self.pascal_id)));
+        } else if let Some(returns) = self.returns.as_mut() {
+            *returns = returns.with_lifetime("'b", borrowing);
+        }
+    }
+}
+
+/// The `x-pagination` extension of an operation
+#[derive(Debug, Deserialize)]
+pub struct PaginationExtension {
+    pub strategy: PaginationStrategy,
+    /// The parameter sent with the cursor or offset of each page
+    pub param: String,
+    /// The response header holding the next cursor, with the `header` strategy
+    pub header: Option<String>,
+    /// A JSON pointer to the next cursor in the body, with the `body` strategy
+    pub cursor: Option<String>,
+    /// The parameter capping the size of a page, with the `offset` strategy
+    pub limit: Option<String>,
+    /// A JSON pointer to the list in the body, when the body is not the list itself
+    pub items: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PaginationStrategy {
+    Header,
+    Body,
+    Link,
+    Offset,
 }
 
 #[derive(Debug, Serialize)]
+pub struct Pagination {
+    pub snake_id: RustSnakeIdentifier,
+    pub description: String,
+    pub item: String,
+    /// The parameters of the operation but the one carrying the cursor
+    pub arguments: Vec<Parameter>,
+    /// The arguments of the operation for each page, unless it takes a params struct
+    pub call: Vec<PageArgument>,
+    pub cursor_type: &'static str,
+    /// The parameter carrying the cursor
+    pub param: Parameter,
+    /// A JSON pointer to the list in the body, when the body is not the list itself
+    pub items: Option<String>,
+    /// The response header holding the next cursor, with the `header` strategy
+    pub header: Option<String>,
+    /// Follows the `Link` header, with the `link` strategy
+    pub link: bool,
+    /// A JSON pointer to the next cursor in the body, with the `body` strategy
+    pub body_cursor: Option<String>,
+    /// Counts the items of each page, with the `offset` strategy
+    pub offset: bool,
+    /// The parameter capping the size of a page, with the `offset` strategy
+    pub limit: Option<RustSnakeIdentifier>,
+}
+
+/// An argument of an operation called for a page
+#[derive(Debug, Serialize)]
+pub struct PageArgument {
+    pub snake_id: RustSnakeIdentifier,
+    /// Takes the cursor of the page rather than the caller's value
+    pub paginates: bool,
+}
+
+/// The item type of a `Vec`
+fn vec_item(r#type: &str) -> Option<String> {
+    if r#type.starts_with("Vec<") && r#type.ends_with('>') {
+        Some(r#type[4..r#type.len() - 1].to_string())
+    } else {
+        None
+    }
+}
+
+/// A response link, followed by calling the operation it leads to
+#[derive(Debug, Serialize)]
+pub struct Link {
+    pub snake_id: RustSnakeIdentifier,
+    pub api_id: String,
+    /// The operation whose response the link is followed from
+    pub source: String,
+    /// The type of that response's body
+    pub from: String,
+    /// The required parameters the link leaves for the caller
+    pub arguments: Vec<Parameter>,
+    pub setup: Vec<String>,
+    pub call: String,
+}
+
+/// A link parameter the generated code can work out from the response
+#[derive(Debug)]
+pub enum LinkValue {
+    /// A `$response.body#/...` runtime expression, holding the JSON pointer
+    Body(String),
+    Constant(String),
+}
+
+impl LinkValue {
+    fn expression(&self, required: bool) -> String {
+        match (self, required) {
+            (LinkValue::Body(pointer), true) => {
+                format!("_internal_links::body_value(response, {:?})?", pointer)
+            }
+            (LinkValue::Body(pointer), false) => {
+                format!(
+                    "_internal_links::optional_body_value(response, {:?})?",
+                    pointer
+                )
+            }
+            (LinkValue::Constant(text), true) => {
+                format!("_internal_style::parse_text({:?})?", text)
+            }
+            (LinkValue::Constant(text), false) => {
+                format!("Some(_internal_style::parse_text({:?})?)", text)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Response {
+    pub pascal_id: RustPascalIdentifier,
+    /// The `match` pattern for the status codes this response covers
+    pub pattern: String,
+    pub range: bool,
+    pub success: bool,
+    pub r#type: Option<RustType>,
+    /// The expression reading the body into `r#type`
+    pub parse: String,
+    /// Borrows from the caller's buffer rather than owning its contents
+    pub borrows: bool,
+    pub headers: Vec<ResponseHeader>,
+}
+
+impl Response {
+    /// Zero-copy success bodies borrow from the caller's buffer, whereas error bodies are
+    /// converted to their `'static` form so they can travel up through `?`
+    fn borrow_types(&mut self, borrowing: &HashSet<String>) {
+        let r#type = match self.r#type.as_ref() {
+            Some(r#type) => r#type,
+            None => return,
+        };
+        let lifetime = if self.success { "'b" } else { "'static" };
+        let borrowed = r#type.with_lifetime(lifetime, borrowing);
+        if borrowed == *r#type {
+            return;
+        }
+
+        self.parse = if self.success {
+            self.borrows = true;
+            "_internal_request::json_borrowed(&mut response, buffer)?".to_string()
+        } else {
+            let parse = format!(
+                "_internal_request::json_borrowed::<{}>(&mut response, &mut Vec::new())?",
+                r#type.with_lifetime("'_", borrowing)
+            );
+            r#type.owned_expression(&parse, borrowing)
+        };
+        self.r#type = Some(borrowed);
+    }
+}
+
+impl From<(&str, &ResponseV3)> for Response {
+    fn from((status, response): (&str, &ResponseV3)) -> Self {
+        let range = status.len() == 3 && status[1..].eq_ignore_ascii_case("XX");
+        let class = &status[..1];
+        let body = response_body(response);
+
+        Response {
+            pascal_id: match status {
+                "default" => "default".to_string(),
+                status => format!("status_{}", status),
+            }
+            .into(),
+            pattern: if range {
+                format!("{}00..={}99", class, class)
+            } else {
+                status.to_owned()
+            },
+            range,
+            success: class == "2",
+            r#type: body.as_ref().map(|(r#type, _)| r#type.clone()),
+            parse: body.map_or(String::new(), |(_, parse)| parse.to_string()),
+            borrows: false,
+            headers: response
+                .headers
+                .iter()
+                .filter_map(|(name, reference_or_header)| match reference_or_header {
+                    ReferenceOr::Item(header) => Some((name.as_str(), header).into()),
+                    ReferenceOr::Reference { .. } => None,
+                })
+                // Content-Type describes the body, so is never declared as a header
+                .filter(|header: &ResponseHeader| {
+                    !header.api_id.eq_ignore_ascii_case("content-type")
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResponseHeader {
+    pub api_id: String,
+    pub snake_id: RustSnakeIdentifier,
+    pub r#type: RustType,
+    pub required: bool,
+}
+
+impl From<(&str, &Header)> for ResponseHeader {
+    fn from((name, header): (&str, &Header)) -> Self {
+        ResponseHeader {
+            api_id: name.to_owned(),
+            snake_id: name.to_owned().into(),
+            r#type: match header.format {
+                ParameterSchemaOrContent::Schema(ref reference_or_schema) => {
+                    reference_or_schema.into()
+                }
+                ParameterSchemaOrContent::Content(ref content) => match content
+                    .values()
+                    .next()
+                    .and_then(|mediatype| mediatype.schema.as_ref())
+                {
+                    Some(reference_or_schema) => reference_or_schema.into(),
+                    None => RustType("String".to_owned()),
+                },
+            },
+            required: header.required,
+        }
+    }
+}
+
+/// The type of the body and the expression reading it. JSON is preferred, plain
+/// `application/json` ahead of types such as `application/problem+json`, then text.
+/// Binary content is streamed, and anything else read into memory as raw bytes.
+fn response_body(response: &ResponseV3) -> Option<(RustType, &'static str)> {
+    let json = response.content.get("application/json").or_else(|| {
+        response
+            .content
+            .iter()
+            .find(|(content_type, _)| content_type.ends_with("+json"))
+            .map(|(_, reference_or_mediatype)| reference_or_mediatype)
+    });
+    if let Some(reference_or_mediatype) = json {
+        return match reference_or_mediatype {
+            ReferenceOr::Item(mediatype) => {
+                mediatype
+                    .schema
+                    .as_ref()
+                    .map(|reference_or_schema| match reference_or_schema {
+                        // An inline schema without a `type` has no model to deserialize into
+                        ReferenceOr::Item(Schema::Any(_)) => {
+                            (RustType("Value".to_owned()), "response.json()?")
+                        }
+                        reference_or_schema => (reference_or_schema.into(), "response.json()?"),
+                    })
+            }
+            _ => unimplemented!(),
+        };
+    }
+
+    let content_types = response.content.keys().collect::<Vec<&String>>();
+    if content_types.is_empty() {
+        None
+    } else if content_types
+        .iter()
+        .any(|content_type| content_type.starts_with("text/"))
+    {
+        Some((RustType("String".to_owned()), "response.text()?"))
+    } else if content_types
+        .iter()
+        .any(|content_type| streamed(content_type))
+    {
+        Some((
+            RustType("ByteStream".to_owned()),
+            "_internal_binary::ByteStream::new(response)",
+        ))
+    } else {
+        Some((
+            RustType("Vec<u8>".to_owned()),
+            "_internal_binary::read_bytes(&mut response)?",
+        ))
+    }
+}
+
+/// Content which may be too large to hold in memory
+fn streamed(content_type: &str) -> bool {
+    ["image/", "audio/", "video/", "*/"]
+        .iter()
+        .any(|prefix| content_type.starts_with(prefix))
+        || [
+            "application/octet-stream",
+            "application/pdf",
+            "application/zip",
+            "application/gzip",
+        ]
+        .contains(&content_type)
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct Parameter {
-    api_id: String,
+    pub api_id: String,
     snake_id: RustSnakeIdentifier,
     r#type: RustType,
     test_value: String,
+    style: String,
+    pub explode: bool,
+    allow_reserved: bool,
+    /// Otherwise taken as an `Option`, and left off the request when `None`
+    required: bool,
+    /// The value the server uses when the parameter is left off
+    pub default: Option<String>,
+    /// The expression serialized into the request, JSON text for a `content` parameter
+    value: String,
 }
 
-impl From<&openapiv3::ParameterData> for Parameter {
-    fn from(parameter_data: &openapiv3::ParameterData) -> Parameter {
+impl From<&openapiv3::Parameter> for Parameter {
+    fn from(parameter: &openapiv3::Parameter) -> Parameter {
+        let (parameter_data, style, allow_reserved) = match parameter {
+            openapiv3::Parameter::Query {
+                parameter_data,
+                style,
+                allow_reserved,
+                ..
+            } => (
+                parameter_data,
+                match style {
+                    QueryStyle::Form => "Form",
+                    QueryStyle::SpaceDelimited => "SpaceDelimited",
+                    QueryStyle::PipeDelimited => "PipeDelimited",
+                    QueryStyle::DeepObject => "DeepObject",
+                },
+                *allow_reserved,
+            ),
+            openapiv3::Parameter::Path {
+                parameter_data,
+                style,
+            } => (
+                parameter_data,
+                match style {
+                    PathStyle::Simple => "Simple",
+                    PathStyle::Label => "Label",
+                    PathStyle::Matrix => "Matrix",
+                },
+                false,
+            ),
+            openapiv3::Parameter::Header { parameter_data, .. } => {
+                (parameter_data, "Simple", false)
+            }
+            openapiv3::Parameter::Cookie { parameter_data, .. } => (parameter_data, "Form", false),
+        };
         let r#type: RustType = parameter_data.into();
+        let snake_id: RustSnakeIdentifier = parameter_data.name.to_owned().into();
+        // Path parameters are always required, whatever the spec says
+        let required = match parameter {
+            openapiv3::Parameter::Path { .. } => true,
+            _ => parameter_data.required,
+        };
+        let content = match parameter_data.format {
+            ParameterSchemaOrContent::Content(_) => true,
+            ParameterSchemaOrContent::Schema(_) => false,
+        };
 
         Parameter {
             api_id: parameter_data.name.to_owned(),
-            snake_id: parameter_data.name.to_owned().into(),
-            test_value: match r#type.0.as_str() {
-                "String" => format!("\"{}\".into()", parameter_data.name),
-                "Vec<String>" => format!(
+            test_value: match (required, r#type.0.as_str()) {
+                (false, _) => "None".into(),
+                (true, "String") => format!("\"{}\".into()", parameter_data.name),
+                (true, "Vec<String>") => format!(
                     "vec![\"{}1\".into(), \"{}2\".into()]",
                     parameter_data.name, parameter_data.name
                 ),
-                t => format!("{}::default()", t),
+                (true, t) => format!("{}::default()", t),
             },
             r#type,
+            style: format!("_internal_style::Style::{}", style),
+            // The style's default, until an explicit `explode` is read from the spec
+            // https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.2.md#parameterExplode
+            explode: style == "Form",
+            allow_reserved,
+            required,
+            // Read from the raw spec, as the typed one does not keep it
+            default: None,
+            value: match (content, required) {
+                (true, true) => format!("&serde_json::to_string(&r#{})?", snake_id),
+                (true, false) => format!(
+                    "&r#{}.as_ref().map(serde_json::to_string).transpose()?",
+                    snake_id
+                ),
+                (false, _) => format!("&r#{}", snake_id),
+            },
+            snake_id,
         }
     }
 }
 
-impl From<&ReferenceOr<RequestBody>> for Parameter {
-    fn from(reference_or_requestbody: &ReferenceOr<RequestBody>) -> Parameter {
-        let r#type: RustType = reference_or_requestbody.into();
+#[derive(Debug, Serialize)]
+pub struct Body {
+    pub snake_id: RustSnakeIdentifier,
+    pub r#type: RustType,
+    pub test_value: String,
+    pub content_type: String,
+    /// The model patched by a merge-patch or JSON Patch body
+    pub patch: Option<RustType>,
+    pub json_patch: bool,
+    /// Sent as it is rather than as JSON
+    pub text: bool,
+    /// Sent as raw bytes, from memory or streamed from a reader
+    pub binary: bool,
+    /// The generated struct sent as a `multipart/form-data` body
+    pub form: Option<Form>,
+}
+
+impl Body {
+    /// A `multipart/form-data` or `application/x-www-form-urlencoded` body
+    pub fn form(form: Form) -> Body {
+        Body {
+            snake_id: "form".to_owned().into(),
+            r#type: RustType(form.pascal_id.to_string()),
+            test_value: format!("{}::default()", form.pascal_id),
+            content_type: form.content_type.clone(),
+            patch: None,
+            json_patch: false,
+            text: false,
+            binary: false,
+            form: Some(form),
+        }
+    }
 
-        Parameter {
-            api_id: "body".to_owned(),
+    /// Switches the body over to the generated `{Model}Patch` type, if the patched model has one
+    pub fn patch_type(&mut self, patchable: &[String]) {
+        match self.patch.take() {
+            Some(ref model) if patchable.contains(&model.to_string()) => {
+                self.r#type = RustType(format!("{}Patch", model));
+                self.test_value = format!("{}::default()", self.r#type);
+                self.patch = Some(model.clone());
+            }
+            _ => self.json_patch = false,
+        }
+    }
+
+    /// A body sent as JSON, as text or as raw bytes, from the first content type it can be sent as
+    pub fn new(
+        reference_or_requestbody: &ReferenceOr<RequestBody>,
+    ) -> Result<Body, failure::Error> {
+        let (r#type, content_type): (RustType, String) = match reference_or_requestbody {
+            ReferenceOr::Item(requestbody) => match requestbody_mediatype(requestbody) {
+                Some((content_type, mediatype)) => match mediatype.schema {
+                    Some(ref reference_or_schema) => {
+                        (reference_or_schema.into(), content_type.to_owned())
+                    }
+                    None => {
+                        return Err(failure::format_err!(
+                            "the {} request body has no schema",
+                            content_type
+                        ))
+                    }
+                },
+                None => match requestbody.content.keys().next() {
+                    Some(content_type) if content_type.starts_with("text/") => {
+                        (RustType("String".to_owned()), content_type.to_owned())
+                    }
+                    // Wildcards such as `image/*` say nothing about what is actually sent
+                    Some(content_type) if !content_type.contains('*') => {
+                        (RustType("Binary".to_owned()), content_type.to_owned())
+                    }
+                    Some(_) => (
+                        RustType("Binary".to_owned()),
+                        "application/octet-stream".to_owned(),
+                    ),
+                    None => return Err(failure::format_err!("the request body has no content")),
+                },
+            },
+            reference => (reference.into(), "application/json".to_owned()),
+        };
+        let raw = match reference_or_requestbody {
+            ReferenceOr::Item(requestbody) => requestbody_mediatype(requestbody).is_none(),
+            ReferenceOr::Reference { .. } => false,
+        };
+
+        Ok(Body {
             snake_id: "body".to_owned().into(),
             test_value: match r#type.0.as_str() {
                 "String" => "\"body\".into()".into(),
                 "Vec<String>" => "vec![\"body1\".into(), \"body2\".into()]".into(),
                 t => format!("{}::default()", t),
             },
+            patch: match content_type.as_str() {
+                "application/merge-patch+json" | "application/json-patch+json" => {
+                    Some(r#type.clone())
+                }
+                _ => None,
+            },
+            json_patch: content_type == "application/json-patch+json",
+            text: raw && r#type.0 == "String",
+            binary: raw && r#type.0 == "Binary",
+            content_type,
             r#type,
+            form: None,
+        })
+    }
+}
+
+fn requestbody_mediatype(requestbody: &RequestBody) -> Option<(&str, &MediaType)> {
+    [
+        "application/json",
+        "application/merge-patch+json",
+        "application/json-patch+json",
+    ]
+    .iter()
+    .filter_map(|content_type| {
+        requestbody
+            .content
+            .get(*content_type)
+            .map(|mediatype| (*content_type, mediatype))
+    })
+    .next()
+}
+
+#[derive(Debug, Serialize)]
+pub struct Form {
+    pub pascal_id: RustPascalIdentifier,
+    pub content_type: String,
+    /// Sent as `application/x-www-form-urlencoded` pairs rather than as multipart parts
+    pub urlencoded: bool,
+    pub fields: Vec<FormField>,
+}
+
+impl Form {
+    /// The `{Operation}Form` struct for an object schema, with its fields described by `encoding`
+    pub fn new(
+        name: String,
+        content_type: &str,
+        schema: &Schema,
+        mediatype: &MediaType,
+    ) -> Result<Form, failure::Error> {
+        let urlencoded = content_type == "application/x-www-form-urlencoded";
+        let (properties, required) = match schema {
+            Schema::Schema(schema_variant) => match schema_variant.as_ref() {
+                SchemaVariant::Object {
+                    properties,
+                    required,
+                    ..
+                } => (properties, required),
+                _ => {
+                    return Err(failure::format_err!(
+                        "the {} body needs an object schema",
+                        content_type
+                    ))
+                }
+            },
+            Schema::Any(any_schema) => (&any_schema.properties, &any_schema.required),
+            _ => {
+                return Err(failure::format_err!(
+                    "the {} body needs an object schema",
+                    content_type
+                ))
+            }
+        };
+
+        Ok(Form {
+            pascal_id: format!("{}_form", name).into(),
+            content_type: content_type.to_owned(),
+            urlencoded,
+            fields: properties
+                .iter()
+                .map(|(name, reference_or_schema)| {
+                    let encoding = mediatype.encoding.get(name);
+                    let mut field = FormField {
+                        api_id: name.to_owned(),
+                        snake_id: name.to_owned().into(),
+                        r#type: reference_or_schema.into(),
+                        required: required.contains(name),
+                        file: false,
+                        values: String::new(),
+                        style: style(encoding.and_then(|encoding| encoding.style.as_ref())),
+                        // The style's default, until an explicit `explode` is read from the spec
+                        explode: matches!(
+                            encoding.and_then(|encoding| encoding.style.as_ref()),
+                            None | Some(QueryStyle::Form)
+                        ),
+                        content_type: match encoding
+                            .map(|e| e.content_type.as_str())
+                            .filter(|s| !s.is_empty())
+                        {
+                            Some(content_type) => format!("Some(\"{}\")", content_type),
+                            None => "None".to_owned(),
+                        },
+                        headers: encoding
+                            .iter()
+                            .flat_map(|encoding| encoding.headers.keys())
+                            .filter(|header| !header.eq_ignore_ascii_case("content-type"))
+                            .map(|header| FormHeader {
+                                api_id: header.to_owned(),
+                                snake_id: format!("{}_{}", name, header).into(),
+                            })
+                            .collect(),
+                    };
+
+                    let binary = if urlencoded {
+                        None
+                    } else {
+                        binary_items(reference_or_schema)
+                    };
+                    let repeated = match binary {
+                        Some(true) => {
+                            field.r#type = RustType("Vec<FilePart>".to_owned());
+                            field.file = true;
+                            true
+                        }
+                        Some(false) => {
+                            field.r#type = RustType("FilePart".to_owned());
+                            field.file = true;
+                            false
+                        }
+                        // Each item of an array is a part of its own, unless it is sent as JSON
+                        None => {
+                            !urlencoded
+                                && is_array(reference_or_schema)
+                                && !field.content_type.contains("json")
+                        }
+                    };
+                    // Every field is added through a loop, so a part can be absent or repeated
+                    field.values = match (field.required, repeated) {
+                        (true, false) => format!("std::iter::once(self.r#{})", field.snake_id),
+                        (true, true) => format!("self.r#{}", field.snake_id),
+                        (false, false) => format!("self.r#{}.into_iter()", field.snake_id),
+                        (false, true) => format!("self.r#{}.into_iter().flatten()", field.snake_id),
+                    };
+                    field
+                })
+                .collect(),
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct FormField {
+    pub api_id: String,
+    pub snake_id: RustSnakeIdentifier,
+    pub r#type: RustType,
+    pub required: bool,
+    /// Sent as a file part, rather than as text
+    pub file: bool,
+    /// An iterator over the values of the part
+    pub values: String,
+    /// How a urlencoded field is split into pairs
+    pub style: String,
+    pub explode: bool,
+    pub content_type: String,
+    /// The headers the spec's `encoding` allows on the part
+    pub headers: Vec<FormHeader>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FormHeader {
+    pub api_id: String,
+    pub snake_id: RustSnakeIdentifier,
+}
+
+fn style(style: Option<&QueryStyle>) -> String {
+    format!(
+        "_internal_style::Style::{}",
+        match style {
+            None | Some(QueryStyle::Form) => "Form",
+            Some(QueryStyle::SpaceDelimited) => "SpaceDelimited",
+            Some(QueryStyle::PipeDelimited) => "PipeDelimited",
+            Some(QueryStyle::DeepObject) => "DeepObject",
         }
+    )
+}
+
+fn is_array(reference_or_schema: &ReferenceOr<Box<Schema>>) -> bool {
+    match reference_or_schema {
+        ReferenceOr::Item(schema) => match schema.as_ref() {
+            Schema::Schema(schema_variant) => {
+                matches!(schema_variant.as_ref(), SchemaVariant::Array { .. })
+            }
+            _ => false,
+        },
+        ReferenceOr::Reference { .. } => false,
+    }
+}
+
+/// Whether the schema is a `format: binary` string, `Some(true)` for an array of them
+fn binary_items(reference_or_schema: &ReferenceOr<Box<Schema>>) -> Option<bool> {
+    let binary = |schema: &Schema| match schema {
+        Schema::Schema(schema_variant) => matches!(
+            schema_variant.as_ref(),
+            SchemaVariant::String {
+                format: VariantOrUnknownOrEmpty::Item(StringFormat::Binary),
+                ..
+            }
+        ),
+        _ => false,
+    };
+
+    match reference_or_schema {
+        ReferenceOr::Item(schema) if binary(schema) => Some(false),
+        ReferenceOr::Item(schema) => match schema.as_ref() {
+            Schema::Schema(schema_variant) => match schema_variant.as_ref() {
+                SchemaVariant::Array {
+                    items: ReferenceOr::Item(items),
+                    ..
+                } if binary(items) => Some(true),
+                _ => None,
+            },
+            _ => None,
+        },
+        ReferenceOr::Reference { .. } => None,
     }
 }
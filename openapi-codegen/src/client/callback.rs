@@ -0,0 +1,287 @@
+use super::resolve_response;
+use crate::RustType;
+use crate::{RustPascalIdentifier, RustSnakeIdentifier};
+use openapiv3::Operation;
+use openapiv3::ReferenceOr;
+use openapiv3::Schema;
+use serde_derive::Serialize;
+use std::collections::HashSet;
+
+/// A callback an operation declares, received through a generated `{Callback}Handler` trait
+#[derive(Debug, Serialize)]
+pub struct Callback {
+    pub api_id: String,
+    pub pascal_id: RustPascalIdentifier,
+    pub snake_id: RustSnakeIdentifier,
+    /// The operation registering the callback
+    pub source: String,
+    pub operations: Vec<CallbackOperation>,
+}
+
+impl Callback {
+    pub fn new(name: &str, source: &str, operations: Vec<CallbackOperation>) -> Self {
+        Callback {
+            api_id: name.to_owned(),
+            pascal_id: name.to_owned().into(),
+            snake_id: name.to_owned().into(),
+            source: source.to_owned(),
+            operations,
+        }
+    }
+
+    /// Qualifies the names with the registering operation, for callbacks sharing a name
+    pub fn qualify(&mut self) {
+        let name = format!("{}_{}", self.source, self.api_id);
+        self.pascal_id = name.clone().into();
+        self.snake_id = name.into();
+    }
+}
+
+/// A request the server sends to the callback URL
+#[derive(Debug, Serialize)]
+pub struct CallbackOperation {
+    pub snake_id: RustSnakeIdentifier,
+    pub pascal_id: RustPascalIdentifier,
+    pub http_method: String,
+    /// The URL as the spec gives it, runtime expressions and all
+    pub expression: String,
+    /// The URL path, and query string if it has one, with each runtime expression as a `*`
+    /// wildcard
+    pub pattern: String,
+    pub body: Option<RustType>,
+    /// The statement reading `body` from the raw bytes of the request
+    pub parse: String,
+    pub responses: Vec<CallbackResponse>,
+}
+
+impl CallbackOperation {
+    /// Reads an operation of a callback, resolving the `$ref` responses within `raw`. The
+    /// zero-copy models in `borrowing` are handed over in their `'static` form, as the request
+    /// they would borrow from is gone once the handler returns.
+    pub fn new(
+        name: String,
+        expression: &str,
+        http_method: &str,
+        operation: &Operation,
+        raw: &serde_yaml::Value,
+        borrowing: &HashSet<String>,
+    ) -> Result<Self, failure::Error> {
+        let body = operation
+            .request_body
+            .as_ref()
+            .map(|reference_or_requestbody| match reference_or_requestbody {
+                ReferenceOr::Reference { .. } => (reference_or_requestbody.into(), true, "json"),
+                ReferenceOr::Item(requestbody) => match content_type(requestbody.content.iter()) {
+                    Some((r#type, kind, _)) => (r#type, requestbody.required, kind),
+                    None => (RustType("Vec<u8>".to_owned()), true, "bytes"),
+                },
+            });
+        let parse = match body.as_ref() {
+            // Zero-copy models are read borrowing from the request, then made `'static`
+            Some((r#type, required, "json"))
+                if r#type.with_lifetime("'static", borrowing) != *r#type =>
+            {
+                let (parse, owned) = if *required {
+                    (
+                        "serde_json::from_slice",
+                        r#type.owned_expression("body", borrowing),
+                    )
+                } else {
+                    (
+                        "_internal_router::optional_json",
+                        RustType(format!("Option<{}>", r#type)).owned_expression("body", borrowing),
+                    )
+                };
+                format!(
+                    "{}::<{}>(body).map(|body| {})",
+                    parse,
+                    r#type.with_lifetime("'_", borrowing),
+                    owned
+                )
+            }
+            Some((_, true, "json")) => "serde_json::from_slice(body)".to_owned(),
+            Some((_, false, "json")) => "_internal_router::optional_json(body)".to_owned(),
+            Some((_, _, "text")) => "String::from_utf8(body.to_vec())".to_owned(),
+            _ => "Ok::<_, failure::Error>(body.to_vec())".to_owned(),
+        };
+
+        Ok(CallbackOperation {
+            pascal_id: name.clone().into(),
+            snake_id: name.into(),
+            http_method: http_method.to_owned(),
+            expression: expression.to_owned(),
+            pattern: pattern(expression),
+            body: body.map(|(r#type, required, _)| {
+                let r#type = r#type.with_lifetime("'static", borrowing);
+                if required {
+                    r#type
+                } else {
+                    RustType(format!("Option<{}>", r#type))
+                }
+            }),
+            parse,
+            responses: operation
+                .responses
+                .responses
+                .iter()
+                .map(|(status, reference_or_response)| (status.as_str(), reference_or_response))
+                .chain(
+                    operation
+                        .responses
+                        .default
+                        .as_ref()
+                        .map(|reference_or_response| ("default", reference_or_response)),
+                )
+                .map(|(status, reference_or_response)| {
+                    let response = match reference_or_response {
+                        ReferenceOr::Item(response) => response.clone(),
+                        ReferenceOr::Reference { reference } => resolve_response(raw, reference)?,
+                    };
+                    Ok(CallbackResponse::new(
+                        status,
+                        borrowing,
+                        content_type(response.content.iter().filter_map(
+                            |(content_type, reference_or_mediatype)| match reference_or_mediatype {
+                                ReferenceOr::Item(mediatype) => Some((content_type, mediatype)),
+                                ReferenceOr::Reference { .. } => None,
+                            },
+                        )),
+                    ))
+                })
+                .collect::<Result<Vec<CallbackResponse>, failure::Error>>()?,
+        })
+    }
+}
+
+/// A documented response to a callback, which the handler picks
+#[derive(Debug, Serialize)]
+pub struct CallbackResponse {
+    pub pascal_id: RustPascalIdentifier,
+    /// The status sent, unless the handler chooses it for a range or `default`
+    pub code: Option<u16>,
+    pub r#type: Option<RustType>,
+    /// The fields of the variant, as bound when matching it
+    pub binding: String,
+    /// The expression building the `hyper::Response`
+    pub respond: String,
+}
+
+impl CallbackResponse {
+    fn new(
+        status: &str,
+        borrowing: &HashSet<String>,
+        body: Option<(RustType, &str, String)>,
+    ) -> Self {
+        let code = status.parse::<u16>().ok();
+        let status_code = match code {
+            Some(code) => code.to_string(),
+            None => "status.as_u16()".to_string(),
+        };
+        let binding = match (code, body.is_some()) {
+            (Some(_), true) => "(body)",
+            (Some(_), false) => "",
+            (None, true) => "(status, body)",
+            (None, false) => "(status)",
+        };
+        let respond = match body.as_ref() {
+            Some((_, "json", _)) => format!("_internal_router::json({}, &body)", status_code),
+            Some((_, kind, content_type)) => format!(
+                "_internal_router::bytes({}, Some({:?}), body{})",
+                status_code,
+                // Wildcard content types cannot be sent as they are
+                if content_type.contains('*') {
+                    "application/octet-stream"
+                } else {
+                    content_type.as_str()
+                },
+                if *kind == "text" { ".into_bytes()" } else { "" }
+            ),
+            None => format!("_internal_router::bytes({}, None, Vec::new())", status_code),
+        };
+
+        CallbackResponse {
+            pascal_id: match status {
+                "default" => "default".to_string(),
+                status => format!("status_{}", status),
+            }
+            .into(),
+            code,
+            r#type: body.map(|(r#type, _, _)| r#type.with_lifetime("'static", borrowing)),
+            binding: binding.to_owned(),
+            respond,
+        }
+    }
+}
+
+/// The type of a body, how it is carried and its content type: JSON first, then text, then
+/// raw bytes
+fn content_type<'a, I>(content: I) -> Option<(RustType, &'static str, String)>
+where
+    I: Iterator<Item = (&'a String, &'a openapiv3::MediaType)>,
+{
+    let content = content.collect::<Vec<(&String, &openapiv3::MediaType)>>();
+    let json = content
+        .iter()
+        .find(|(content_type, _)| *content_type == "application/json")
+        .or_else(|| {
+            content
+                .iter()
+                .find(|(content_type, _)| content_type.ends_with("+json"))
+        });
+    if let Some((content_type, mediatype)) = json {
+        let r#type = match mediatype.schema.as_ref() {
+            // An inline schema without a `type` has no model to deserialize into
+            Some(ReferenceOr::Item(Schema::Any(_))) | None => RustType("Value".to_owned()),
+            Some(reference_or_schema) => reference_or_schema.into(),
+        };
+        return Some((r#type, "json", content_type.to_string()));
+    }
+    let text = content
+        .iter()
+        .find(|(content_type, _)| content_type.starts_with("text/"));
+    match text.or_else(|| content.first()) {
+        Some((content_type, _)) if content_type.starts_with("text/") => Some((
+            RustType("String".to_owned()),
+            "text",
+            content_type.to_string(),
+        )),
+        Some((content_type, _)) => Some((
+            RustType("Vec<u8>".to_owned()),
+            "bytes",
+            content_type.to_string(),
+        )),
+        None => None,
+    }
+}
+
+/// The path and query string of a callback URL, with runtime expressions such as
+/// `{$request.query.url}` as wildcards. An expression may stand for a whole URL, so a literal
+/// scheme and host are dropped.
+fn pattern(expression: &str) -> String {
+    let mut pattern = String::new();
+    let mut depth = 0;
+    for c in expression.chars() {
+        match c {
+            '{' => {
+                if depth == 0 {
+                    pattern.push('*');
+                }
+                depth += 1;
+            }
+            '}' if depth > 0 => depth -= 1,
+            c if depth == 0 => pattern.push(c),
+            _ => {}
+        }
+    }
+    match pattern.find("://") {
+        Some(scheme) => {
+            let rest = &pattern[scheme + 3..];
+            match rest.find(['/', '?']) {
+                Some(path) if rest[path..].starts_with('/') => rest[path..].to_owned(),
+                Some(query) => format!("/{}", &rest[query..]),
+                None => "/".to_owned(),
+            }
+        }
+        None => pattern,
+    }
+}
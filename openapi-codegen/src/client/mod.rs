@@ -1,6 +1,10 @@
-use crate::client::api::Api;
-use crate::client::api::Method;
-use crate::client::model::DataType;
+use crate::client::api::{Api, Runtime};
+use crate::client::api::{Body, Form, LinkValue, Method, PaginationExtension};
+use crate::client::callback::{Callback, CallbackOperation};
+use crate::client::model::{borrow_models, DataType};
+use crate::client::security::{security_requirements, SecurityScheme};
+use crate::client::server::Server;
+use crate::RustSnakeIdentifier;
 use failure::Error;
 use handlebars::Handlebars;
 use openapiv3::OpenAPI;
@@ -8,21 +12,47 @@ use openapiv3::Operation;
 use openapiv3::Parameter;
 use openapiv3::PathItem;
 use openapiv3::ReferenceOr;
+use openapiv3::RequestBody;
+use openapiv3::Response;
+use openapiv3::Schema;
 use serde_derive::Serialize;
 use serde_yaml;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs::{DirBuilder, File};
 use std::io::Write;
 use std::path::Path;
 
 mod api;
+mod callback;
 mod model;
+mod options;
+mod security;
+mod server;
+
+pub use self::options::{Grouping, Options, TagPlacement};
 
 pub fn client(api_path: &str, output_dir: &str, tests: bool) -> Result<(), Error> {
+    client_with_options(
+        api_path,
+        output_dir,
+        Options {
+            tests,
+            ..Options::default()
+        },
+    )
+}
+
+pub fn client_with_options(
+    api_path: &str,
+    output_dir: &str,
+    options: Options,
+) -> Result<(), Error> {
     let mut reg = Handlebars::new();
     reg.register_escape_fn(handlebars::no_escape);
     reg.register_template_string("api_mod", include_str!("resources/api_mod.mustache"))?;
     reg.register_template_string("api", include_str!("resources/api.mustache"))?;
+    reg.register_template_string("api_submod", include_str!("resources/api_submod.mustache"))?;
+    reg.register_template_string("client", include_str!("resources/client.mustache"))?;
     reg.register_template_string("model_enum", include_str!("resources/model_enum.mustache"))?;
     reg.register_template_string("model_mod", include_str!("resources/model_mod.mustache"))?;
     reg.register_template_string(
@@ -34,46 +64,217 @@ pub fn client(api_path: &str, output_dir: &str, tests: bool) -> Result<(), Error
         include_str!("resources/model_newtype.mustache"),
     )?;
     reg.register_template_string("mod", include_str!("resources/mod.mustache"))?;
+    reg.register_template_string("callbacks", include_str!("resources/callbacks.mustache"))?;
+    reg.register_template_string("servers", include_str!("resources/servers.mustache"))?;
+    reg.register_template_string(
+        "credentials",
+        include_str!("resources/credentials.mustache"),
+    )?;
+    reg.register_template_string("request", include_str!("resources/request.mustache"))?;
+    reg.register_template_string("style", include_str!("resources/style.mustache"))?;
+    reg.register_template_string("binary", include_str!("resources/binary.mustache"))?;
+    reg.register_template_string("multipart", include_str!("resources/multipart.mustache"))?;
+    reg.register_template_string("pagination", include_str!("resources/pagination.mustache"))?;
+    reg.register_template_string("links", include_str!("resources/links.mustache"))?;
+    reg.register_template_string("borrow", include_str!("resources/borrow.mustache"))?;
+
+    if !options.public_fields && !options.accessors {
+        return Err(failure::format_err!(
+            "models without `public_fields` need their `accessors`, or could not be built or read"
+        ));
+    }
 
     let dest_path = Path::new(&output_dir);
-    DirBuilder::new().recursive(true).create(&dest_path)?;
+    DirBuilder::new().recursive(true).create(dest_path)?;
 
     let spec: OpenAPI = serde_yaml::from_reader(File::open(api_path)?)?;
+    // Vendor extensions, and settings which cannot be told apart from their defaults, are not
+    // kept by the typed spec
+    let raw: serde_yaml::Value = serde_yaml::from_reader(File::open(api_path)?)?;
 
     DirBuilder::new()
         .recursive(true)
-        .create(&dest_path.join("apis"))?;
+        .create(dest_path.join("apis"))?;
 
-    let mut configuration = File::create(&dest_path.join("apis/configuration.rs"))?;
+    let mut configuration = File::create(dest_path.join("apis/configuration.rs"))?;
     configuration.write_all(include_bytes!("resources/configuration.rs"))?;
 
-    let mut request = File::create(&dest_path.join("apis/request.rs"))?;
-    request.write_all(include_bytes!("resources/request.rs"))?;
+    let mut apis = spec_apis(&spec, &raw, &options)?;
+
+    let mut models = match spec.components.as_ref() {
+        Some(components) => components
+            .schemas
+            .iter()
+            .map(|(name, schema)| DataType::from((name.clone(), schema.clone())))
+            .map(|model| model.with_options(&options))
+            .collect::<Vec<DataType>>(),
+        None => vec![],
+    };
+
+    patch_models(&mut apis, &mut models);
+    explode_form_fields(&mut apis, &raw);
+    raw_parameters(&mut apis, &raw);
+
+    let parameter_types = apis
+        .iter()
+        .flat_map(|api| api.methods.iter())
+        .flat_map(|method| method.parameter_types())
+        .flat_map(|r#type| r#type.names())
+        .map(|name| name.to_string())
+        .collect::<HashSet<String>>();
+    for model in models.iter_mut() {
+        if let DataType::NewType(newtype) = model {
+            newtype.parameter = parameter_types.contains(&newtype.pascal_id.to_string());
+        }
+    }
+
+    let borrowing = if options.borrowed_models {
+        borrow_models(&mut models)
+    } else {
+        HashSet::new()
+    };
+    for method in apis.iter_mut().flat_map(|api| api.methods.iter_mut()) {
+        method.borrow_types(&borrowing);
+    }
+
+    if options.params_structs {
+        for method in apis.iter_mut().flat_map(|api| api.methods.iter_mut()) {
+            method.with_params_struct();
+        }
+    }
+
+    secure_methods(&mut apis, &raw);
+    paginate_methods(&mut apis, &raw, &models)?;
+    link_methods(&mut apis, &raw);
+    let (servers, default) = spec_servers(&mut apis, &raw);
+    share_operation_types(&mut apis);
+    check_names(&apis)?;
+
+    let servers_file = File::create(dest_path.join("apis/servers.rs"))?;
+    let servers = Servers { default, servers };
+    reg.render_to_write("servers", &servers, servers_file)?;
+
+    let security_schemes = yaml_entries(&raw["components"]["securitySchemes"])
+        .into_iter()
+        .filter_map(|(name, scheme)| SecurityScheme::new(name, resolve_yaml(&raw, scheme)))
+        .collect::<Vec<SecurityScheme>>();
+    let credentials = File::create(dest_path.join("apis/credentials.rs"))?;
+    reg.render_to_write("credentials", &security_schemes, credentials)?;
+
+    let mut runtime = Runtime::default();
+    for api in apis.iter_mut() {
+        for method in api.methods.iter() {
+            api.runtime.merge(&method.runtime());
+        }
+        runtime.merge(&api.runtime);
+    }
+    for scheme in security_schemes.iter() {
+        match scheme.r#type.as_str() {
+            // The bundled signers need crates which only specs signing their requests depend on
+            "Signature" => runtime.uses("signing"),
+            "Token" => runtime.uses("oauth"),
+            _ => {}
+        }
+    }
+    runtime.complete();
+
+    let request = File::create(dest_path.join("apis/request.rs"))?;
+    reg.render_to_write("request", &runtime, request)?;
 
-    let apis = spec_apis(&spec, tests);
+    let style = File::create(dest_path.join("apis/style.rs"))?;
+    reg.render_to_write("style", &runtime, style)?;
 
-    let api_mod = File::create(&dest_path.join("apis/mod.rs"))?;
-    reg.render_to_write("api_mod", &apis, api_mod)?;
+    if runtime.has("binary_module") {
+        let binary = File::create(dest_path.join("apis/binary.rs"))?;
+        reg.render_to_write("binary", &runtime, binary)?;
+    }
+
+    if runtime.has("multipart") {
+        let multipart = File::create(dest_path.join("apis/multipart.rs"))?;
+        reg.render_to_write("multipart", &runtime, multipart)?;
+    }
+
+    if runtime.has("pagination") {
+        let pagination = File::create(dest_path.join("apis/pagination.rs"))?;
+        reg.render_to_write("pagination", &runtime, pagination)?;
+    }
+
+    if runtime.has("links") {
+        let links = File::create(dest_path.join("apis/links.rs"))?;
+        reg.render_to_write("links", &runtime, links)?;
+    }
+
+    if runtime.has("oauth") {
+        let mut oauth = File::create(dest_path.join("apis/oauth.rs"))?;
+        oauth.write_all(include_bytes!("resources/oauth.rs"))?;
+    }
+
+    if runtime.has("signing") {
+        let mut signing = File::create(dest_path.join("apis/signing.rs"))?;
+        signing.write_all(include_bytes!("resources/signing.rs"))?;
+    }
+
+    for (path, module) in api_modules(&apis, &runtime) {
+        let module_path = path
+            .iter()
+            .fold(dest_path.join("apis"), |module_path, segment| {
+                module_path.join(segment.to_string())
+            });
+        DirBuilder::new().recursive(true).create(&module_path)?;
+
+        let mod_file = File::create(module_path.join("mod.rs"))?;
+        if path.is_empty() {
+            reg.render_to_write("api_mod", &module, mod_file)?;
+        } else {
+            reg.render_to_write("api_submod", &module, mod_file)?;
+        }
+    }
 
     for api in &apis {
-        let api_file = File::create(&dest_path.join(format!("apis/{}_api.rs", api.snake_id)))?;
+        let api_path = api
+            .module
+            .iter()
+            .fold(dest_path.join("apis"), |api_path, segment| {
+                api_path.join(segment.to_string())
+            });
+        let api_file = File::create(api_path.join(format!("{}_api.rs", api.snake_id)))?;
         reg.render_to_write("api", &api, api_file)?;
     }
 
-    let models = match spec.components {
-        Some(components) => components
-            .schemas
-            .into_iter()
-            .map(|entry| entry.into())
-            .collect::<Vec<DataType>>(),
-        None => vec![],
+    let root_client = RootClient {
+        apis: &apis,
+        flat: options.grouping == Grouping::Flat,
+        default: servers.default.is_some(),
     };
+    let client_file = File::create(dest_path.join("apis/client.rs"))?;
+    reg.render_to_write("client", &root_client, client_file)?;
 
     let models_path = dest_path.join("models");
     DirBuilder::new().recursive(true).create(&models_path)?;
 
+    let patched = models.iter().any(|model| match model {
+        DataType::Struct(_struct) => _struct.patch,
+        _ => false,
+    });
+    if patched {
+        let mut patch = File::create(models_path.join("patch.rs"))?;
+        patch.write_all(include_bytes!("resources/patch.rs"))?;
+    }
+
+    let borrow = BorrowHelpers::new(&models);
+    if borrow.option_str || borrow.vec_str {
+        let file = File::create(models_path.join("borrow.rs"))?;
+        reg.render_to_write("borrow", &borrow, file)?;
+    }
+
+    let model_mod = ModelMod {
+        models: &models,
+        borrowed: borrow.option_str || borrow.vec_str,
+        patch: patched,
+    };
+
     let models_mod = File::create(models_path.join("mod.rs"))?;
-    reg.render_to_write("model_mod", &models, models_mod)?;
+    reg.render_to_write("model_mod", &model_mod, models_mod)?;
 
     for model in &models {
         match model {
@@ -92,6 +293,18 @@ pub fn client(api_path: &str, output_dir: &str, tests: bool) -> Result<(), Error
         }
     }
 
+    let callbacks = spec_callbacks(&apis, &raw, &borrowing)?;
+    if !callbacks.is_empty() {
+        let callbacks_path = dest_path.join("callbacks");
+        DirBuilder::new().recursive(true).create(&callbacks_path)?;
+
+        let mut router = File::create(callbacks_path.join("router.rs"))?;
+        router.write_all(include_bytes!("resources/router.rs"))?;
+
+        let callbacks_mod = File::create(callbacks_path.join("mod.rs"))?;
+        reg.render_to_write("callbacks", &callbacks, callbacks_mod)?;
+    }
+
     let mod_file = if output_dir == "src" {
         File::create(dest_path.join("lib.rs"))?
     } else {
@@ -100,6 +313,7 @@ pub fn client(api_path: &str, output_dir: &str, tests: bool) -> Result<(), Error
 
     let r#mod = Mod {
         root: output_dir == "src",
+        callbacks: !callbacks.is_empty(),
     };
 
     reg.render_to_write("mod", &r#mod, mod_file)?;
@@ -110,155 +324,948 @@ pub fn client(api_path: &str, output_dir: &str, tests: bool) -> Result<(), Error
 #[derive(Debug, Serialize)]
 struct Mod {
     root: bool,
+    callbacks: bool,
 }
 
-fn spec_apis(spec: &OpenAPI, tests: bool) -> Vec<Api> {
-    paths_tags(spec)
-        .into_iter()
-        .map(|tag| Api {
-            snake_id: tag.clone().unwrap_or("untagged".to_string()).into(),
-            pascal_id: tag.clone().unwrap_or("untagged".to_string()).into(),
-            methods: spec
-                .paths
-                .iter()
-                .filter(|(_path, reference_or_operations)| {
-                    operations_tags(reference_or_operations).contains(&tag)
-                })
-                .flat_map(|(path, reference_or_operations)| {
-                    operations_methods(path, reference_or_operations)
-                })
-                .collect(),
-            tests,
+/// A module of `apis`, declaring its nested modules and its clients
+#[derive(Debug, Serialize)]
+struct ApiModule<'a> {
+    /// The parts of the runtime the clients use, which the root module declares
+    runtime: &'a Runtime,
+    modules: Vec<RustSnakeIdentifier>,
+    apis: Vec<&'a Api>,
+}
+
+#[derive(Debug, Serialize)]
+struct RootClient<'a> {
+    apis: &'a [Api],
+    /// Dereferences to the one client of the `Flat` grouping
+    flat: bool,
+    /// The spec declares a server for `Client::default()`
+    default: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct Servers {
+    servers: Vec<Server>,
+    /// The first server of the spec, without which there is no default configuration
+    default: Option<RustSnakeIdentifier>,
+}
+
+#[derive(Debug, Serialize)]
+struct ModelMod<'a> {
+    models: &'a [DataType],
+    borrowed: bool,
+    /// Some model has a `{Model}Patch`, which needs the `Patch` types
+    patch: bool,
+}
+
+/// The `borrow` helpers which zero-copy fields deserialize with
+#[derive(Serialize)]
+struct BorrowHelpers {
+    option_str: bool,
+    vec_str: bool,
+}
+
+impl BorrowHelpers {
+    fn new(models: &[DataType]) -> Self {
+        let helpers = models
+            .iter()
+            .flat_map(|model| match model {
+                DataType::Struct(_struct) => _struct
+                    .optional_fields
+                    .iter()
+                    .chain(_struct.required_fields.iter())
+                    .collect(),
+                _ => vec![],
+            })
+            .filter_map(|field| field.deserialize_with.as_ref())
+            .collect::<HashSet<&String>>();
+        BorrowHelpers {
+            option_str: helpers.contains(&"super::borrow::option_str".to_string()),
+            vec_str: helpers.contains(&"super::borrow::vec_str".to_string()),
+        }
+    }
+}
+
+/// The modules holding the clients by their path within `apis`, starting with `apis` itself
+fn api_modules<'a>(
+    apis: &'a [Api],
+    runtime: &'a Runtime,
+) -> Vec<(Vec<RustSnakeIdentifier>, ApiModule<'a>)> {
+    let mut paths: Vec<&[RustSnakeIdentifier]> = Vec::new();
+    paths.push(&[]);
+    for api in apis {
+        for depth in 1..=api.module.len() {
+            let prefix = &api.module[..depth];
+            if !paths.contains(&prefix) {
+                paths.push(prefix);
+            }
+        }
+    }
+
+    paths
+        .iter()
+        .map(|path| {
+            let module = ApiModule {
+                runtime,
+                modules: paths
+                    .iter()
+                    .filter(|other| other.len() == path.len() + 1 && other.starts_with(path))
+                    .map(|other| other[path.len()].clone())
+                    .collect(),
+                apis: apis
+                    .iter()
+                    .filter(|api| api.module.as_slice() == *path)
+                    .collect(),
+            };
+            (path.to_vec(), module)
         })
         .collect()
 }
 
-fn operations_methods(path: &str, reference_or_operations: &ReferenceOr<PathItem>) -> Vec<Method> {
-    match reference_or_operations {
-        ReferenceOr::Reference { .. } => unimplemented!(),
-        ReferenceOr::Item(operations) => {
-            let options =
-                vec![
-                    operations.get.as_ref().map(|operation| {
-                        operation_method("GET".into(), path.to_owned(), &operation)
-                    }),
-                    operations.post.as_ref().map(|operation| {
-                        operation_method("POST".into(), path.to_owned(), &operation)
-                    }),
-                    operations.put.as_ref().map(|operation| {
-                        operation_method("PUT".into(), path.to_owned(), &operation)
-                    }),
-                    operations.patch.as_ref().map(|operation| {
-                        operation_method("PATCH".into(), path.to_owned(), &operation)
-                    }),
-                    operations.delete.as_ref().map(|operation| {
-                        operation_method("DELETE".into(), path.to_owned(), &operation)
-                    }),
-                ];
-
-            options.into_iter().filter_map(|o| o).collect()
+/// Gives each group a client, in the order its first operation appears among the paths
+fn spec_apis(
+    spec: &OpenAPI,
+    raw: &serde_yaml::Value,
+    options: &Options,
+) -> Result<Vec<Api>, Error> {
+    let mut apis = Vec::<Api>::new();
+    for (path, reference_or_operations) in spec.paths.iter() {
+        let path_item = match reference_or_operations {
+            ReferenceOr::Reference { .. } => unimplemented!(),
+            ReferenceOr::Item(path_item) => path_item,
+        };
+        for (method, operation) in path_item_operations(path_item) {
+            let groups = match options.grouping {
+                Grouping::Tag | Grouping::NestedTag => {
+                    let mut tags = operation_tags(operation);
+                    if options.tag_placement == TagPlacement::Primary {
+                        tags.truncate(1);
+                    }
+                    tags.into_iter()
+                        .map(|tag| tag.unwrap_or_else(|| "untagged".to_string()))
+                        .collect()
+                }
+                Grouping::PathSegment => vec![path_segment(path)],
+                Grouping::Flat => vec!["operations".to_string()],
+            };
+            for group in groups {
+                let method = operation_method(
+                    spec,
+                    raw,
+                    method.into(),
+                    path.to_owned(),
+                    operation,
+                    &path_item.parameters,
+                )?;
+                let index = match apis.iter().position(|api| api.api_id == group) {
+                    Some(index) => index,
+                    None => {
+                        let api = Api::new(
+                            &group,
+                            options.grouping == Grouping::NestedTag,
+                            options.tests,
+                        );
+                        if let Some(other) = apis.iter().find(|other| {
+                            other.accessor == api.accessor
+                                || (other.module == api.module && other.snake_id == api.snake_id)
+                        }) {
+                            return Err(failure::format_err!(
+                                "the `{}` and `{}` clients have the same name",
+                                other.api_id,
+                                api.api_id
+                            ));
+                        }
+                        apis.push(api);
+                        apis.len() - 1
+                    }
+                };
+                apis[index].methods.push(method);
+            }
         }
     }
+    Ok(apis)
 }
 
-fn operation_method(method: String, path: String, operation: &Operation) -> Method {
-    Method {
-        snake_id: match operation.operation_id.as_ref() {
-            Some(operation_id) => operation_id.to_owned(),
-            None => format!("{}/{}", method, path),
+/// Gives every struct sent as a merge-patch or JSON Patch body a `{Model}Patch` companion
+fn patch_models(apis: &mut [Api], models: &mut [DataType]) {
+    let patched = apis
+        .iter()
+        .flat_map(|api| api.methods.iter())
+        .filter_map(|method| method.body.as_ref())
+        .filter_map(|body| body.patch.as_ref())
+        .map(|model| model.to_string())
+        .collect::<HashSet<String>>();
+
+    let mut patchable = Vec::new();
+    for model in models.iter_mut() {
+        if let DataType::Struct(_struct) = model {
+            if patched.contains(&_struct.pascal_id.to_string()) {
+                _struct.patch = true;
+                patchable.push(_struct.pascal_id.to_string());
+            }
         }
-        .into(),
-        path: path,
-        http_method: method,
-        path_parameters: operation
-            .parameters
+    }
+
+    for body in apis
+        .iter_mut()
+        .flat_map(|api| api.methods.iter_mut())
+        .filter_map(|method| method.body.as_mut())
+    {
+        body.patch_type(&patchable);
+    }
+}
+
+/// Leaves the types of an operation held by several clients to the first of them, which the
+/// others import them from, so each type is defined and exported once
+fn share_operation_types(apis: &mut [Api]) {
+    for index in 1..apis.len() {
+        let (owners, rest) = apis.split_at_mut(index);
+        let api = &mut rest[0];
+        let root = "super::".repeat(api.module.len() + 1);
+        for method in api.methods.iter_mut() {
+            let owner = owners.iter().find(|owner| {
+                owner.methods.iter().any(|other| {
+                    other.path == method.path && other.http_method == method.http_method
+                })
+            });
+            if let Some(owner) = owner {
+                method.shared_types = Some(format!(
+                    "{}{}{{{}}}",
+                    root,
+                    owner.path,
+                    method.type_names().join(", ")
+                ));
+            }
+        }
+    }
+}
+
+/// Checks that no two operations generate the same method in a client, or the same type in a
+/// module, where the types of its clients are all exported
+fn check_names(apis: &[Api]) -> Result<(), Error> {
+    for api in apis {
+        let mut methods = HashMap::<String, &Method>::new();
+        for method in &api.methods {
+            for name in method.method_names() {
+                if name == "new" || name == "shared" {
+                    return Err(failure::format_err!(
+                        "{} {} is `{}`, which the `{}` client already has, so it needs an operationId",
+                        method.http_method,
+                        method.path,
+                        name,
+                        api.api_id
+                    ));
+                }
+                if let Some(other) = methods.insert(name.clone(), method) {
+                    return Err(failure::format_err!(
+                        "{} {} and {} {} both generate `{}` in the `{}` client, so one needs an operationId",
+                        other.http_method,
+                        other.path,
+                        method.http_method,
+                        method.path,
+                        name,
+                        api.api_id
+                    ));
+                }
+            }
+        }
+    }
+
+    // The types of an operation held by several clients are defined by the first of them only
+    let mut types = HashMap::<(&str, String), &Method>::new();
+    for api in apis {
+        for method in api
+            .methods
             .iter()
-            .filter_map(|reference_or_parameter| {
-                if let ReferenceOr::Item(parameter) = reference_or_parameter {
-                    Some(parameter)
-                } else {
-                    None
+            .filter(|method| method.shared_types.is_none())
+        {
+            for name in method.type_names() {
+                if let Some(other) = types.insert((&api.path, name.clone()), method) {
+                    return Err(failure::format_err!(
+                        "{} {} and {} {} both generate `{}`, so one needs an operationId",
+                        other.http_method,
+                        other.path,
+                        method.http_method,
+                        method.path,
+                        name
+                    ));
                 }
-            })
-            .filter_map(|parameter| {
-                if let Parameter::Path { parameter_data, .. } = parameter {
-                    Some(parameter_data)
-                } else {
-                    None
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Takes an explicit `explode` from each form encoding, which the typed spec cannot tell from a
+/// missing one
+fn explode_form_fields(apis: &mut [Api], raw: &serde_yaml::Value) {
+    for method in apis.iter_mut().flat_map(|api| api.methods.iter_mut()) {
+        let requestbody = resolve_yaml(
+            raw,
+            &raw["paths"][method.path.as_str()][method.http_method.to_lowercase().as_str()]
+                ["requestBody"],
+        );
+        let form = match method.body.as_mut().and_then(|body| body.form.as_mut()) {
+            Some(form) => form,
+            None => continue,
+        };
+        let encoding = &requestbody["content"][form.content_type.as_str()]["encoding"];
+        for field in form.fields.iter_mut() {
+            if let Some(explode) = encoding[field.api_id.as_str()]["explode"].as_bool() {
+                field.explode = explode;
+            }
+        }
+    }
+}
+
+/// Takes what the typed spec does not keep from each parameter: an explicit `explode`, and the
+/// `default` of its schema
+fn raw_parameters(apis: &mut [Api], raw: &serde_yaml::Value) {
+    for method in apis.iter_mut().flat_map(|api| api.methods.iter_mut()) {
+        let path = method.path.clone();
+        let http_method = method.http_method.clone();
+        for (location, parameter) in method.parameters_mut() {
+            let raw_parameter =
+                match raw_parameter(raw, &path, &http_method, location, &parameter.api_id) {
+                    Some(raw_parameter) => raw_parameter,
+                    None => continue,
+                };
+            if let Some(explode) = raw_parameter["explode"].as_bool() {
+                parameter.explode = explode;
+            }
+            let default = &resolve_yaml(raw, &raw_parameter["schema"])["default"];
+            if !default.is_null() {
+                parameter.default = Some(json_text(default));
+            }
+        }
+    }
+}
+
+/// A YAML value written as JSON
+fn json_text(value: &serde_yaml::Value) -> String {
+    match value {
+        serde_yaml::Value::Null => "null".to_owned(),
+        serde_yaml::Value::Bool(boolean) => boolean.to_string(),
+        serde_yaml::Value::Number(number) => number.to_string(),
+        serde_yaml::Value::String(string) => format!("{:?}", string),
+        serde_yaml::Value::Sequence(items) => format!(
+            "[{}]",
+            items
+                .iter()
+                .map(json_text)
+                .collect::<Vec<String>>()
+                .join(",")
+        ),
+        serde_yaml::Value::Mapping(entries) => format!(
+            "{{{}}}",
+            entries
+                .iter()
+                .map(|(key, value)| format!("{}:{}", json_text(key), json_text(value)))
+                .collect::<Vec<String>>()
+                .join(",")
+        ),
+    }
+}
+
+/// The parameter `name` sent in `location`, declared by the operation or else by its path item
+fn raw_parameter<'a>(
+    raw: &'a serde_yaml::Value,
+    path: &str,
+    http_method: &str,
+    location: &str,
+    name: &str,
+) -> Option<&'a serde_yaml::Value> {
+    let path_item = &raw["paths"][path];
+    let operation = &path_item[http_method.to_lowercase().as_str()];
+    operation["parameters"]
+        .as_sequence()
+        .into_iter()
+        .flatten()
+        .chain(path_item["parameters"].as_sequence().into_iter().flatten())
+        .map(|parameter| resolve_yaml(raw, parameter))
+        .find(|parameter| {
+            parameter["in"].as_str() == Some(location) && parameter["name"].as_str() == Some(name)
+        })
+}
+
+/// Gives each operation its own `security`, or else the spec's
+fn secure_methods(apis: &mut [Api], raw: &serde_yaml::Value) {
+    for method in apis.iter_mut().flat_map(|api| api.methods.iter_mut()) {
+        let operation =
+            &raw["paths"][method.path.as_str()][method.http_method.to_lowercase().as_str()];
+        // An empty list on the operation removes the spec's requirements
+        method.security = if operation["security"].is_null() {
+            security_requirements(&raw["security"])
+        } else {
+            security_requirements(&operation["security"])
+        };
+    }
+}
+
+/// Gathers the servers of the spec, its paths and its operations into the `Server` enum, and
+/// points each operation with servers of its own at the first of them. Also gives the first
+/// server of the spec itself, if it has one.
+fn spec_servers(
+    apis: &mut [Api],
+    raw: &serde_yaml::Value,
+) -> (Vec<Server>, Option<RustSnakeIdentifier>) {
+    let mut servers = Vec::new();
+    let default = add_servers(&mut servers, &raw["servers"]);
+
+    for method in apis.iter_mut().flat_map(|api| api.methods.iter_mut()) {
+        let path_item = &raw["paths"][method.path.as_str()];
+        let operation = &path_item[method.http_method.to_lowercase().as_str()];
+        let entries = if operation["servers"].is_null() {
+            &path_item["servers"]
+        } else {
+            &operation["servers"]
+        };
+        method.server = add_servers(&mut servers, entries).map(|snake_id| snake_id.to_string());
+    }
+    (servers, default)
+}
+
+/// Adds the servers of a `servers` list which are not there yet, giving the first of the list
+fn add_servers(
+    servers: &mut Vec<Server>,
+    entries: &serde_yaml::Value,
+) -> Option<RustSnakeIdentifier> {
+    let mut first = None;
+    for entry in entries.as_sequence().into_iter().flatten() {
+        let url = entry["url"].as_str();
+        let index = match servers
+            .iter()
+            .position(|server| Some(server.url.as_str()) == url)
+        {
+            Some(index) => index,
+            None => {
+                let taken = servers
+                    .iter()
+                    .map(|server| &server.pascal_id)
+                    .collect::<Vec<_>>();
+                match Server::new(entry, &taken) {
+                    Some(server) => {
+                        servers.push(server);
+                        servers.len() - 1
+                    }
+                    None => continue,
+                }
+            }
+        };
+        first = first.or(Some(index));
+    }
+    first.map(|index| servers[index].snake_id.clone())
+}
+
+/// Reads the `x-pagination` extension of each operation
+fn paginate_methods(
+    apis: &mut [Api],
+    raw: &serde_yaml::Value,
+    models: &[DataType],
+) -> Result<(), Error> {
+    for method in apis.iter_mut().flat_map(|api| api.methods.iter_mut()) {
+        let extension = &raw["paths"][method.path.as_str()]
+            [method.http_method.to_lowercase().as_str()]["x-pagination"];
+        if extension.is_null() {
+            continue;
+        }
+        let extension: PaginationExtension = serde_yaml::from_value(extension.clone())?;
+        method.paginate(extension, models)?;
+    }
+    Ok(())
+}
+
+/// Gives the target of each response link a method following it from the response's body
+fn link_methods(apis: &mut [Api], raw: &serde_yaml::Value) {
+    let mut links = Vec::new();
+    for (path, path_item) in yaml_entries(&raw["paths"]) {
+        for (http_method, operation) in yaml_entries(path_item) {
+            let source = match find_method(apis, path, http_method) {
+                Some(source) => source,
+                None => continue,
+            };
+            for (status, response) in yaml_entries(&operation["responses"]) {
+                let from = match source.response_type(status) {
+                    Some(from) => from,
+                    None => continue,
+                };
+                for (name, link) in yaml_entries(&resolve_yaml(raw, response)["links"]) {
+                    let link = resolve_yaml(raw, link);
+                    let target = match link_target(raw, link) {
+                        Some(target) => target,
+                        None => continue,
+                    };
+                    let values = yaml_entries(&link["parameters"])
+                        .into_iter()
+                        .filter_map(|(parameter, value)| {
+                            link_value(value).map(|value| (parameter.to_owned(), value))
+                        })
+                        .collect::<Vec<(String, LinkValue)>>();
+                    links.push((
+                        target,
+                        name.to_owned(),
+                        source.snake_id.clone(),
+                        from.clone(),
+                        values,
+                    ));
                 }
+            }
+        }
+    }
+
+    for ((path, http_method), name, source, from, values) in links {
+        for method in apis
+            .iter_mut()
+            .flat_map(|api| api.methods.iter_mut())
+            .filter(|method| method.path == path && method.http_method == http_method)
+        {
+            method.add_link(&name, &source, &from, &values);
+        }
+    }
+}
+
+/// Reads the `callbacks` of each operation, naming them after the operation too when two share
+/// a name
+fn spec_callbacks(
+    apis: &[Api],
+    raw: &serde_yaml::Value,
+    borrowing: &HashSet<String>,
+) -> Result<Vec<Callback>, Error> {
+    let mut callbacks = Vec::new();
+    for (path, path_item) in yaml_entries(&raw["paths"]) {
+        for (http_method, operation) in yaml_entries(path_item) {
+            let source = match find_method(apis, path, http_method) {
+                Some(source) => source,
+                None => continue,
+            };
+            for (name, callback) in yaml_entries(&operation["callbacks"]) {
+                let mut operations = Vec::new();
+                let expressions = yaml_entries(resolve_yaml(raw, callback));
+                for (index, (expression, path_item)) in expressions.iter().enumerate() {
+                    let path_item: PathItem =
+                        serde_yaml::from_value(resolve_yaml(raw, path_item).clone())?;
+                    for (http_method, operation) in path_item_operations(&path_item) {
+                        let name = match operation.operation_id.as_ref() {
+                            Some(operation_id) => operation_id.to_owned(),
+                            None if expressions.len() > 1 => {
+                                format!("{}_{}", http_method.to_lowercase(), index + 1)
+                            }
+                            None => http_method.to_lowercase(),
+                        };
+                        operations.push(CallbackOperation::new(
+                            name,
+                            expression,
+                            http_method,
+                            operation,
+                            raw,
+                            borrowing,
+                        )?);
+                    }
+                }
+                callbacks.push(Callback::new(
+                    name,
+                    &source.snake_id.to_string(),
+                    operations,
+                ));
+            }
+        }
+    }
+
+    let names = callbacks
+        .iter()
+        .map(|callback| callback.api_id.clone())
+        .collect::<Vec<String>>();
+    for callback in callbacks.iter_mut() {
+        if names
+            .iter()
+            .filter(|name| **name == callback.api_id)
+            .count()
+            > 1
+        {
+            callback.qualify();
+        }
+    }
+
+    Ok(callbacks)
+}
+
+/// The path and method of the operation a link leads to, by `operationId` or `operationRef`
+fn link_target(raw: &serde_yaml::Value, link: &serde_yaml::Value) -> Option<(String, String)> {
+    if let Some(operation_id) = link["operationId"].as_str() {
+        return yaml_entries(&raw["paths"])
+            .into_iter()
+            .flat_map(|(path, path_item)| {
+                yaml_entries(path_item)
+                    .into_iter()
+                    .map(move |(http_method, operation)| (path, http_method, operation))
+            })
+            .find(|(_, _, operation)| operation["operationId"].as_str() == Some(operation_id))
+            .map(|(path, http_method, _)| (path.to_owned(), http_method.to_uppercase()));
+    }
+    // Only references within this document are followed
+    let reference = link["operationRef"].as_str()?;
+    let mut segments = reference
+        .trim_start_matches("#/")
+        .split('/')
+        .map(unescape_pointer);
+    match (segments.next(), segments.next(), segments.next()) {
+        (Some(ref paths), Some(path), Some(http_method)) if paths == "paths" => {
+            Some((path, http_method.to_uppercase()))
+        }
+        _ => None,
+    }
+}
+
+/// Body pointers and constants can be filled in, whereas the other runtime expressions are
+/// left to the caller
+fn link_value(value: &serde_yaml::Value) -> Option<LinkValue> {
+    match value {
+        serde_yaml::Value::String(expression) if expression.starts_with("$response.body#") => Some(
+            LinkValue::Body(expression.trim_start_matches("$response.body#").to_owned()),
+        ),
+        serde_yaml::Value::String(expression) if expression.contains('$') => None,
+        serde_yaml::Value::String(constant) => Some(LinkValue::Constant(constant.to_owned())),
+        serde_yaml::Value::Number(constant) => Some(LinkValue::Constant(constant.to_string())),
+        serde_yaml::Value::Bool(constant) => Some(LinkValue::Constant(constant.to_string())),
+        _ => None,
+    }
+}
+
+fn find_method<'a>(apis: &'a [Api], path: &str, http_method: &str) -> Option<&'a Method> {
+    apis.iter()
+        .flat_map(|api| api.methods.iter())
+        .find(|method| method.path == path && method.http_method.eq_ignore_ascii_case(http_method))
+}
+
+fn yaml_entries(value: &serde_yaml::Value) -> Vec<(&str, &serde_yaml::Value)> {
+    value
+        .as_mapping()
+        .map(|mapping| {
+            mapping
+                .iter()
+                .filter_map(|(key, value)| key.as_str().map(|key| (key, value)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Follows a `$ref` within the document
+fn resolve_yaml<'a>(
+    raw: &'a serde_yaml::Value,
+    value: &'a serde_yaml::Value,
+) -> &'a serde_yaml::Value {
+    match value["$ref"]
+        .as_str()
+        .and_then(|reference| yaml_target(raw, reference))
+    {
+        Some(target) => resolve_yaml(raw, target),
+        None => value,
+    }
+}
+
+/// What a `#/...` reference points to within the document
+fn yaml_target<'a>(raw: &'a serde_yaml::Value, reference: &str) -> Option<&'a serde_yaml::Value> {
+    reference.strip_prefix("#/").map(|pointer| {
+        pointer
+            .split('/')
+            .map(unescape_pointer)
+            .fold(raw, |value, segment| &value[segment.as_str()])
+    })
+}
+
+/// The response a `$ref` points to within the document
+fn resolve_response(raw: &serde_yaml::Value, reference: &str) -> Result<Response, Error> {
+    match yaml_target(raw, reference) {
+        Some(target) if !target.is_null() => {
+            Ok(serde_yaml::from_value(resolve_yaml(raw, target).clone())?)
+        }
+        _ => Err(failure::format_err!(
+            "{} is not a response of the spec",
+            reference
+        )),
+    }
+}
+
+/// The parameter a `$ref` points to within the document
+fn resolve_parameter(raw: &serde_yaml::Value, reference: &str) -> Result<Parameter, Error> {
+    match yaml_target(raw, reference) {
+        Some(target) if !target.is_null() => {
+            Ok(serde_yaml::from_value(resolve_yaml(raw, target).clone())?)
+        }
+        _ => Err(failure::format_err!(
+            "{} is not a parameter of the spec",
+            reference
+        )),
+    }
+}
+
+fn unescape_pointer(segment: &str) -> String {
+    segment.replace("~1", "/").replace("~0", "~")
+}
+
+/// The first segment of `path` which can name a client, skipping parameters and ones such as
+/// the `2.0` of a versioned path
+fn path_segment(path: &str) -> String {
+    path.split('/')
+        .find(|segment| segment.starts_with(char::is_alphabetic))
+        .unwrap_or("root")
+        .to_owned()
+}
+
+/// Names an operation without an `operationId` after its method and the segments of its path,
+/// with each `{param}` read as `by_param`: `GET /pets/{petId}` becomes `get_pets_by_pet_id`
+fn method_name(method: &str, path: &str) -> String {
+    let mut name = vec![method.to_lowercase()];
+    for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+        if segment.starts_with('{') && segment.ends_with('}') {
+            name.push(format!("by_{}", &segment[1..segment.len() - 1]));
+        } else {
+            name.push(segment.to_owned());
+        }
+    }
+    name.join("_")
+}
+
+fn path_item_operations(path_item: &PathItem) -> Vec<(&'static str, &Operation)> {
+    vec![
+        ("GET", &path_item.get),
+        ("POST", &path_item.post),
+        ("PUT", &path_item.put),
+        ("PATCH", &path_item.patch),
+        ("DELETE", &path_item.delete),
+        ("HEAD", &path_item.head),
+        ("OPTIONS", &path_item.options),
+        ("TRACE", &path_item.trace),
+    ]
+    .into_iter()
+    .filter_map(|(method, operation)| operation.as_ref().map(|operation| (method, operation)))
+    .collect()
+}
+
+fn operation_method(
+    spec: &OpenAPI,
+    raw: &serde_yaml::Value,
+    method: String,
+    path: String,
+    operation: &Operation,
+    path_item_parameters: &[ReferenceOr<Parameter>],
+) -> Result<Method, Error> {
+    let operation_parameters = parameter_items(raw, &operation.parameters)
+        .map_err(|e| failure::format_err!("{} {}: {}", method, path, e))?;
+    // Operation parameters override any path item parameter with the same name and location
+    let parameters = parameter_items(raw, path_item_parameters)
+        .map_err(|e| failure::format_err!("{} {}: {}", method, path, e))?
+        .into_iter()
+        .filter(|parameter| {
+            !operation_parameters
+                .iter()
+                .any(|overriding| parameter_key(overriding) == parameter_key(parameter))
+        })
+        .chain(operation_parameters.iter().cloned())
+        .collect::<Vec<Parameter>>();
+
+    // HEAD responses never carry a body, only the headers a GET would have sent
+    let head = method == "HEAD";
+    let operation_id = match operation.operation_id.as_ref() {
+        Some(operation_id) => operation_id.to_owned(),
+        None => method_name(&method, &path),
+    };
+
+    let body = match operation.request_body.as_ref() {
+        Some(reference_or_requestbody) => Some(
+            operation_body(spec, &operation_id, reference_or_requestbody)
+                .map_err(|e| failure::format_err!("{} {}: {}", method, path, e))?,
+        ),
+        None => None,
+    };
+
+    let responses = operation
+        .responses
+        .responses
+        .iter()
+        .map(|(status, reference_or_response)| response_item(raw, status, reference_or_response))
+        .collect::<Result<Vec<api::Response>, Error>>()
+        .map_err(|e| failure::format_err!("{} {}: {}", method, path, e))?;
+    let default = match operation.responses.default.as_ref() {
+        Some(reference_or_response) => Some(
+            response_item(raw, "default", reference_or_response)
+                .map_err(|e| failure::format_err!("{} {}: {}", method, path, e))?,
+        ),
+        None => None,
+    };
+
+    Ok(Method {
+        snake_id: operation_id.clone().into(),
+        pascal_id: operation_id.clone().into(),
+        path,
+        http_method: method,
+        path_parameters: parameters
+            .iter()
+            .filter_map(|parameter| match parameter {
+                Parameter::Path { .. } => Some(parameter.into()),
+                _ => None,
             })
-            .map(|parameter_data| parameter_data.into())
             .collect(),
-        query_parameters: operation
-            .parameters
+        query_parameters: parameters
             .iter()
-            .filter_map(|reference_or_parameter| {
-                if let ReferenceOr::Item(parameter) = reference_or_parameter {
-                    Some(parameter)
-                } else {
-                    None
-                }
+            .filter_map(|parameter| match parameter {
+                Parameter::Query { .. } => Some(parameter.into()),
+                _ => None,
             })
-            .filter_map(|parameter| {
-                if let Parameter::Query { parameter_data, .. } = parameter {
-                    Some(parameter_data)
-                } else {
-                    None
+            .collect(),
+        header_parameters: parameters
+            .iter()
+            .filter_map(|parameter| match parameter {
+                // The spec has these set through the body, security and accepted responses instead
+                Parameter::Header { parameter_data, .. } => {
+                    match parameter_data.name.to_lowercase().as_str() {
+                        "accept" | "content-type" | "authorization" => None,
+                        _ => Some(parameter.into()),
+                    }
                 }
+                _ => None,
             })
-            .map(|parameter_data| parameter_data.into())
             .collect(),
-        body: operation
-            .request_body
-            .as_ref()
-            .map(|parameter| parameter.into()),
-        returns: operation
-            .responses
-            .responses
-            .get("200")
-            .and_then(|reference_or_response| match reference_or_response {
-                ReferenceOr::Item(response) => response.content.get("application/json"),
-                _ => unimplemented!(),
+        cookie_parameters: parameters
+            .iter()
+            .filter_map(|parameter| match parameter {
+                Parameter::Cookie { .. } => Some(parameter.into()),
+                _ => None,
             })
-            .and_then(|reference_or_mediatype| match reference_or_mediatype {
-                ReferenceOr::Item(mediatype) => mediatype.schema.as_ref(),
-                _ => unimplemented!(),
+            .collect(),
+        body,
+        returns: None,
+        responses: vec![],
+        default: None,
+        success_enum: false,
+        documented_success: false,
+        response_generics: String::new(),
+        head,
+        borrows: false,
+        response_headers: vec![],
+        params: false,
+        params_fields: vec![],
+        params_default: false,
+        params_generics: String::new(),
+        pagination: None,
+        page_headers: false,
+        links: vec![],
+        security: "&[]".to_string(),
+        server: None,
+        shared_types: None,
+    }
+    .with_responses(responses, default))
+}
+
+/// A JSON body, or failing that a form sent as a generated struct
+fn operation_body(
+    spec: &OpenAPI,
+    operation_id: &str,
+    reference_or_requestbody: &ReferenceOr<RequestBody>,
+) -> Result<Body, Error> {
+    if let ReferenceOr::Item(requestbody) = reference_or_requestbody {
+        let form = ["multipart/form-data", "application/x-www-form-urlencoded"]
+            .iter()
+            .filter_map(|content_type| {
+                requestbody
+                    .content
+                    .get(*content_type)
+                    .map(|mediatype| (*content_type, mediatype))
             })
-            .map(|reference_or_schema| reference_or_schema.into()),
+            .next();
+        if let (Some((content_type, mediatype)), None) =
+            (form, requestbody.content.get("application/json"))
+        {
+            let schema = match mediatype.schema.as_ref() {
+                Some(reference_or_schema) => resolve_schema(spec, reference_or_schema)?,
+                None => {
+                    return Err(failure::format_err!(
+                        "the {} request body has no schema",
+                        content_type
+                    ))
+                }
+            };
+            return Ok(Body::form(Form::new(
+                operation_id.to_owned(),
+                content_type,
+                schema,
+                mediatype,
+            )?));
+        }
+    }
+    Body::new(reference_or_requestbody)
+}
+
+fn resolve_schema<'a>(
+    spec: &'a OpenAPI,
+    reference_or_schema: &'a ReferenceOr<Schema>,
+) -> Result<&'a Schema, Error> {
+    match reference_or_schema {
+        ReferenceOr::Item(schema) => Ok(schema),
+        ReferenceOr::Reference { reference } => {
+            let name = reference.trim_start_matches("#/components/schemas/");
+            match spec
+                .components
+                .as_ref()
+                .and_then(|components| components.schemas.get(name))
+            {
+                Some(reference_or_schema) => resolve_schema(spec, reference_or_schema),
+                None => Err(failure::format_err!(
+                    "{} is not a schema of the spec",
+                    reference
+                )),
+            }
+        }
     }
 }
 
-fn paths_tags(spec: &OpenAPI) -> HashSet<Option<String>> {
-    spec.paths
+fn response_item(
+    raw: &serde_yaml::Value,
+    status: &str,
+    reference_or_response: &ReferenceOr<Response>,
+) -> Result<api::Response, Error> {
+    match reference_or_response {
+        ReferenceOr::Item(response) => Ok((status, response).into()),
+        ReferenceOr::Reference { reference } => {
+            Ok((status, &resolve_response(raw, reference)?).into())
+        }
+    }
+}
+
+fn parameter_items(
+    raw: &serde_yaml::Value,
+    reference_or_parameters: &[ReferenceOr<Parameter>],
+) -> Result<Vec<Parameter>, Error> {
+    reference_or_parameters
         .iter()
-        .flat_map(|(_, operations)| operations_tags(operations))
+        .map(|reference_or_parameter| match reference_or_parameter {
+            ReferenceOr::Item(parameter) => Ok(parameter.clone()),
+            ReferenceOr::Reference { reference } => resolve_parameter(raw, reference),
+        })
         .collect()
 }
 
-fn operations_tags(reference_or_operations: &ReferenceOr<PathItem>) -> Vec<Option<String>> {
-    match reference_or_operations {
-        ReferenceOr::Reference { .. } => unimplemented!(),
-        ReferenceOr::Item(operations) => {
-            let mut tags = operation_tags(operations.get.as_ref());
-            tags.append(&mut operation_tags(operations.post.as_ref()));
-            tags.append(&mut operation_tags(operations.put.as_ref()));
-            tags.append(&mut operation_tags(operations.patch.as_ref()));
-            tags.append(&mut operation_tags(operations.delete.as_ref()));
-
-            tags
-        }
+fn parameter_key(parameter: &Parameter) -> (&str, &str) {
+    match parameter {
+        Parameter::Query { parameter_data, .. } => ("query", &parameter_data.name),
+        Parameter::Header { parameter_data, .. } => ("header", &parameter_data.name),
+        Parameter::Path { parameter_data, .. } => ("path", &parameter_data.name),
+        Parameter::Cookie { parameter_data, .. } => ("cookie", &parameter_data.name),
     }
 }
 
-fn operation_tags(operation: Option<&Operation>) -> Vec<Option<String>> {
-    match operation {
-        Some(operation) => {
-            if operation.tags.is_empty() {
-                vec![None]
-            } else {
-                operation.tags.iter().map(|s| Some(s.to_string())).collect()
+fn operation_tags(operation: &Operation) -> Vec<Option<String>> {
+    if operation.tags.is_empty() {
+        vec![None]
+    } else {
+        let mut tags = Vec::new();
+        for tag in &operation.tags {
+            if !tags.contains(&Some(tag.to_string())) {
+                tags.push(Some(tag.to_string()));
             }
         }
-        None => Vec::new(),
+        tags
     }
 }
@@ -1,8 +1,10 @@
+use crate::client::Options;
 use crate::{RustPascalIdentifier, RustSnakeIdentifier, RustType};
 use openapiv3::Schema;
 use openapiv3::{AnySchema, ReferenceOr, SchemaVariant};
 use serde_derive::Serialize;
 use std::borrow::Borrow;
+use std::collections::HashSet;
 
 #[derive(Debug, Serialize)]
 #[serde(untagged)]
@@ -12,6 +14,93 @@ pub enum DataType {
     NewType(NewType),
 }
 
+impl DataType {
+    pub fn with_options(mut self, options: &Options) -> Self {
+        match self {
+            DataType::Struct(ref mut _struct) => {
+                _struct.public_fields = options.public_fields;
+                _struct.accessors = options.accessors;
+                _struct.non_exhaustive = options.non_exhaustive;
+            }
+            DataType::NewType(ref mut newtype) => {
+                newtype.public_fields = options.public_fields;
+                newtype.accessors = options.accessors;
+            }
+            DataType::Enum(_) => {}
+        }
+        self
+    }
+}
+
+/// Rewrites models into their zero-copy form, returning the names of those which now borrow
+pub fn borrow_models(models: &mut [DataType]) -> HashSet<String> {
+    let mut borrowing = HashSet::new();
+    loop {
+        let borrowed = borrowing.len();
+        for model in models.iter() {
+            let (name, fields) = match model {
+                DataType::Struct(_struct) => (
+                    _struct.pascal_id.to_string(),
+                    _struct
+                        .optional_fields
+                        .iter()
+                        .chain(_struct.required_fields.iter())
+                        .collect::<Vec<&Field>>(),
+                ),
+                DataType::NewType(newtype) => (newtype.pascal_id.to_string(), vec![&newtype.inner]),
+                DataType::Enum(_) => continue,
+            };
+            if fields.iter().any(|field| {
+                field
+                    .r#type
+                    .names()
+                    .iter()
+                    .any(|name| *name == "String" || borrowing.contains(*name))
+            }) {
+                borrowing.insert(name);
+            }
+        }
+        if borrowing.len() == borrowed {
+            break;
+        }
+    }
+
+    for model in models.iter_mut() {
+        match model {
+            DataType::Struct(_struct) if borrowing.contains(&_struct.pascal_id.to_string()) => {
+                _struct.generics = "<'a>".to_string();
+                for field in _struct.optional_fields.iter_mut() {
+                    let expression = format!("self.r#{}", field.snake_id);
+                    field.make_borrowed(&expression, true, &borrowing);
+                }
+                for field in _struct.required_fields.iter_mut() {
+                    let expression = format!("self.r#{}", field.snake_id);
+                    field.make_borrowed(&expression, false, &borrowing);
+                }
+            }
+            DataType::NewType(newtype) if borrowing.contains(&newtype.pascal_id.to_string()) => {
+                newtype.generics = "<'a>".to_string();
+                newtype.inner.make_borrowed("self.0", false, &borrowing);
+                newtype.item = newtype.item.as_ref().map(|item| item.cow(&borrowing));
+            }
+            _ => {}
+        }
+    }
+
+    let patches = models
+        .iter()
+        .filter_map(|model| match model {
+            DataType::Struct(_struct) if _struct.patch && !_struct.generics.is_empty() => {
+                Some(format!("{}Patch", _struct.pascal_id))
+            }
+            _ => None,
+        })
+        .collect::<Vec<String>>();
+    borrowing.extend(patches);
+
+    borrowing
+}
+
 impl From<(String, ReferenceOr<Schema>)> for DataType {
     fn from((name, reference_or_schema): (String, ReferenceOr<Schema>)) -> Self {
         match reference_or_schema {
@@ -21,20 +110,19 @@ impl From<(String, ReferenceOr<Schema>)> for DataType {
                     DataType::Struct((name, any_schema.borrow() as &AnySchema).into())
                 }
                 Schema::Schema(schema_variant) => match schema_variant.borrow() {
-                    SchemaVariant::String { .. } => {
+                    SchemaVariant::String { enumeration, .. } if !enumeration.is_empty() => {
                         DataType::Enum((name, schema_variant.borrow()).into())
                     }
                     SchemaVariant::Object { .. } => {
                         DataType::Struct((name, schema_variant.borrow() as &SchemaVariant).into())
                     }
-                    SchemaVariant::Array { .. } => {
+                    SchemaVariant::Array { .. }
+                    | SchemaVariant::String { .. }
+                    | SchemaVariant::Number { .. }
+                    | SchemaVariant::Integer { .. }
+                    | SchemaVariant::Boolean { .. } => {
                         DataType::NewType((name, schema_variant.borrow() as &SchemaVariant).into())
                     }
-
-                    e => {
-                        dbg!(e);
-                        unimplemented!()
-                    }
                 },
                 _ => unimplemented!(),
             },
@@ -48,6 +136,11 @@ pub struct Struct {
     pub snake_id: RustSnakeIdentifier,
     pub optional_fields: Vec<Field>,
     pub required_fields: Vec<Field>,
+    pub public_fields: bool,
+    pub accessors: bool,
+    pub non_exhaustive: bool,
+    pub patch: bool,
+    pub generics: String,
 }
 
 impl From<(String, &SchemaVariant)> for Struct {
@@ -62,15 +155,20 @@ impl From<(String, &SchemaVariant)> for Struct {
                 pascal_id: name.clone().into(),
                 snake_id: name.into(),
                 optional_fields: properties
-                    .into_iter()
+                    .iter()
                     .filter(|(name, _)| !required.contains(name))
                     .map(|entry| entry.into())
                     .collect::<Vec<Field>>(),
                 required_fields: properties
-                    .into_iter()
+                    .iter()
                     .filter(|(name, _)| required.contains(name))
                     .map(|entry| entry.into())
                     .collect::<Vec<Field>>(),
+                public_fields: false,
+                accessors: true,
+                non_exhaustive: false,
+                patch: false,
+                generics: String::new(),
             }
         } else {
             unimplemented!()
@@ -95,6 +193,11 @@ impl From<(String, &AnySchema)> for Struct {
                 .filter(|(name, _)| any_schema.required.contains(name))
                 .map(|entry| entry.into())
                 .collect::<Vec<Field>>(),
+            public_fields: false,
+            accessors: true,
+            non_exhaustive: false,
+            patch: false,
+            generics: String::new(),
         }
     }
 }
@@ -104,22 +207,43 @@ pub struct NewType {
     pub pascal_id: RustPascalIdentifier,
     pub snake_id: RustSnakeIdentifier,
     pub inner: Field,
+    pub public_fields: bool,
+    pub accessors: bool,
+    pub generics: String,
+    /// The element type of a collection newtype
+    pub item: Option<RustType>,
+    /// Used as an operation parameter, so needs `Display` and `FromStr`
+    pub parameter: bool,
+    /// Wraps a string, so can be viewed as a `str`
+    pub string: bool,
 }
 
 impl From<(String, &SchemaVariant)> for NewType {
     fn from((name, schema_variant): (String, &SchemaVariant)) -> Self {
-        if let SchemaVariant::Array { items, .. } = schema_variant {
-            let mut inner: Field = (&String::from("inner"), items).into();
-            inner.r#type = RustType(format!("Vec<{}>", inner.r#type.0));
-            inner.borrowed_type = inner.r#type.borrowed();
-
-            NewType {
-                pascal_id: name.clone().into(),
-                snake_id: name.into(),
-                inner,
+        let (inner, item) = match schema_variant {
+            SchemaVariant::Array { items, .. } => {
+                let mut inner: Field = (&String::from("inner"), items).into();
+                let item = inner.r#type.clone();
+                inner.r#type = RustType(format!("Vec<{}>", inner.r#type.0));
+                inner.borrowed_type = inner.r#type.borrowed();
+                (inner, Some(item))
             }
-        } else {
-            unimplemented!()
+            scalar => {
+                let schema = ReferenceOr::Item(Box::new(Schema::Schema(Box::new(scalar.clone()))));
+                ((&String::from("inner"), &schema).into(), None)
+            }
+        };
+
+        NewType {
+            pascal_id: name.clone().into(),
+            snake_id: name.into(),
+            string: inner.r#type.0 == "String",
+            inner,
+            public_fields: false,
+            accessors: true,
+            generics: String::new(),
+            item,
+            parameter: false,
         }
     }
 }
@@ -127,11 +251,36 @@ impl From<(String, &SchemaVariant)> for NewType {
 #[derive(Debug, Serialize)]
 pub struct Field {
     pub api_id: String,
+    pub pointer: String,
     pub snake_id: RustSnakeIdentifier,
     pub r#type: RustType,
     pub borrowed_type: RustType,
     pub nullable: bool,
     pub default: String,
+    pub serde_borrow: bool,
+    pub deserialize_with: Option<String>,
+    /// Converts the zero-copy field into its `'static` form
+    pub owned: String,
+}
+
+impl Field {
+    fn make_borrowed(&mut self, expression: &str, optional: bool, borrowing: &HashSet<String>) {
+        let owned_type = if optional {
+            RustType(format!("Option<{}>", self.r#type))
+        } else {
+            self.r#type.clone()
+        };
+        self.owned = owned_type.owned_expression(expression, borrowing);
+        // serde only borrows a `Cow` it deserializes directly, so wrapped strings need a hand
+        self.deserialize_with = match (optional, self.r#type.0.as_str()) {
+            (true, "String") => Some("super::borrow::option_str".to_string()),
+            (false, "Vec<String>") => Some("super::borrow::vec_str".to_string()),
+            _ => None,
+        };
+        self.r#type = self.r#type.cow(borrowing);
+        self.borrowed_type = self.r#type.borrowed();
+        self.serde_borrow = self.r#type.0.contains("'a");
+    }
 }
 
 impl From<(&String, &ReferenceOr<Box<Schema>>)> for Field {
@@ -140,6 +289,7 @@ impl From<(&String, &ReferenceOr<Box<Schema>>)> for Field {
 
         Field {
             api_id: name.to_owned(),
+            pointer: format!("/{}", name.replace('~', "~0").replace('/', "~1")),
             snake_id: name.to_owned().into(),
             borrowed_type: r#type.borrowed(),
             nullable: match reference_or_schema {
@@ -161,6 +311,9 @@ impl From<(&String, &ReferenceOr<Box<Schema>>)> for Field {
                 "String" => format!("\"{}\".into()", name),
                 t => format!("{}::default()", t),
             },
+            serde_borrow: false,
+            deserialize_with: None,
+            owned: String::new(),
             r#type,
         }
     }
@@ -180,7 +333,7 @@ impl From<(String, &SchemaVariant)> for Enum {
                 pascal_id: name.clone().into(),
                 snake_id: name.into(),
                 variants: enumeration
-                    .into_iter()
+                    .iter()
                     .map(|value| value.into())
                     .collect::<Vec<Variant>>(),
             }
@@ -0,0 +1,59 @@
+use serde_derive::Serialize;
+
+/// Settings controlling the shape of the generated client
+#[derive(Debug, Clone, Serialize)]
+pub struct Options {
+    /// Generate tests which run each operation against an apisprout mock server
+    pub tests: bool,
+    /// Make model fields `pub`, so models can be built, destructured and matched directly
+    pub public_fields: bool,
+    /// Generate `set_`, `with_`, `reset_` and getter methods on models
+    pub accessors: bool,
+    /// Mark generated structs `#[non_exhaustive]`
+    pub non_exhaustive: bool,
+    /// Generate zero-copy `Foo<'a>` models which borrow strings from the response body
+    pub borrowed_models: bool,
+    /// Have each operation take an `{Operation}Params` struct instead of positional parameters
+    pub params_structs: bool,
+    /// Which clients receive an operation with several tags
+    pub tag_placement: TagPlacement,
+    /// How operations are grouped into the clients of the root `Client`
+    pub grouping: Grouping,
+}
+
+/// Which clients receive an operation with several tags
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum TagPlacement {
+    /// The client of each of its tags
+    Duplicate,
+    /// Only the client of its first tag
+    Primary,
+}
+
+/// How operations are grouped into clients
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum Grouping {
+    /// A client for each tag
+    Tag,
+    /// A client for each tag, in nested modules for tags such as `admin/users`
+    NestedTag,
+    /// A client for each first segment of the paths
+    PathSegment,
+    /// A single client for every operation, which the root `Client` dereferences to
+    Flat,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            tests: false,
+            public_fields: false,
+            accessors: true,
+            non_exhaustive: false,
+            borrowed_models: false,
+            params_structs: false,
+            tag_placement: TagPlacement::Duplicate,
+            grouping: Grouping::Tag,
+        }
+    }
+}
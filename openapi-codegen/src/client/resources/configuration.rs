@@ -1,36 +1,113 @@
 use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::credentials::Credentials;
+use super::servers::Server;
 
 #[derive(Clone)]
 pub struct Configuration {
     pub base_path: String,
     pub user_agent: Option<String>,
     pub client: reqwest::Client,
-    pub basic_auth: Option<BasicAuth>,
-    pub oauth_access_token: Option<String>,
-    pub api_key: Option<ApiKey>,
+    pub credentials: Credentials,
     pub cookies: CookieJar,
-    // TODO: take an oauth2 token source, similar to the Go one
+    /// Signs every request, after the signatures of its operation's security schemes
+    pub signer: Option<Signature>,
+    /// The servers of operations with servers of their own, by the name of their method. Any
+    /// other such operation is sent to `base_path` if it was changed from the first server of
+    /// the spec, and to the first server the operation declares otherwise.
+    pub servers: HashMap<String, Server>,
 }
 
 pub type BasicAuth = (String, Option<String>);
 pub type CookieJar = HashMap<String, String>;
 
-#[derive(Clone)]
-pub struct ApiKey {
-    pub prefix: Option<String>,
-    pub key: String,
-}
-
 impl Configuration {
     pub fn new(base_path: String) -> Configuration {
         Configuration {
             base_path,
             user_agent: Some("OpenAPI-Generator/1.9.0/rust".to_owned()),
             client: reqwest::Client::new(),
-            basic_auth: None,
-            oauth_access_token: None,
-            api_key: None,
+            credentials: Credentials::default(),
             cookies: CookieJar::new(),
+            signer: None,
+            servers: HashMap::new(),
         }
     }
 }
+
+/// Supplies OAuth 2.0 access tokens, fetching new ones as they expire
+pub trait TokenSource: Send + Sync {
+    /// A token that is valid now, from a cache if there is one
+    fn token(&self) -> Result<String, failure::Error>;
+
+    /// Drops a cached token which the server rejected, so the next call fetches another
+    fn invalidate(&self) {}
+}
+
+/// A shared `TokenSource`, held in the `Credentials` slot of an OAuth 2.0 or OpenID Connect
+/// scheme
+#[derive(Clone)]
+pub struct Token(Arc<dyn TokenSource>);
+
+impl Token {
+    pub fn new<S: TokenSource + 'static>(source: S) -> Self {
+        Token(Arc::new(source))
+    }
+
+    /// A token obtained elsewhere, which is sent as it is
+    pub fn fixed(token: String) -> Self {
+        Token::new(Fixed(token))
+    }
+
+    pub(crate) fn access_token(&self) -> Result<String, failure::Error> {
+        self.0.token()
+    }
+
+    pub(crate) fn invalidate(&self) {
+        self.0.invalidate()
+    }
+}
+
+impl From<String> for Token {
+    fn from(token: String) -> Self {
+        Token::fixed(token)
+    }
+}
+
+struct Fixed(String);
+
+impl TokenSource for Fixed {
+    fn token(&self) -> Result<String, failure::Error> {
+        Ok(self.0.clone())
+    }
+}
+
+/// Signs requests once they are fully built, just before they are sent
+pub trait Signer: Send + Sync {
+    /// Adds a signature to `request`, whose `body` is `None` when it is streamed
+    fn sign(
+        &self,
+        request: &mut reqwest::Request,
+        body: Option<&[u8]>,
+    ) -> Result<(), failure::Error>;
+}
+
+/// A shared `Signer`, held in the `Credentials` slot of a scheme with an `x-signature`
+/// extension or in `Configuration::signer`
+#[derive(Clone)]
+pub struct Signature(Arc<dyn Signer>);
+
+impl Signature {
+    pub fn new<S: Signer + 'static>(signer: S) -> Self {
+        Signature(Arc::new(signer))
+    }
+
+    pub(crate) fn sign(
+        &self,
+        request: &mut reqwest::Request,
+        body: Option<&[u8]>,
+    ) -> Result<(), failure::Error> {
+        self.0.sign(request, body)
+    }
+}
@@ -0,0 +1,266 @@
+use serde::Deserialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::configuration::TokenSource;
+
+/// Tokens are fetched again this many seconds before they expire, so none lapses in flight
+const EXPIRY_MARGIN: u64 = 30;
+
+/// The OAuth 2.0 client credentials flow, where the client authenticates as itself
+pub struct ClientCredentials {
+    endpoint: Endpoint,
+    client_id: String,
+    client_secret: String,
+    scopes: Vec<String>,
+    client: reqwest::Client,
+    cache: Cache,
+}
+
+impl ClientCredentials {
+    pub fn new(token_url: String, client_id: String, client_secret: String) -> Self {
+        ClientCredentials {
+            endpoint: Endpoint::Token(token_url),
+            client_id,
+            client_secret,
+            scopes: vec![],
+            client: reqwest::Client::new(),
+            cache: Cache::default(),
+        }
+    }
+
+    /// Reads the token endpoint from the OpenID Connect discovery document at
+    /// `openid_connect_url` on first use
+    pub fn discover(openid_connect_url: String, client_id: String, client_secret: String) -> Self {
+        ClientCredentials {
+            endpoint: Endpoint::discover(openid_connect_url),
+            ..ClientCredentials::new(String::new(), client_id, client_secret)
+        }
+    }
+
+    pub fn with_scopes(mut self, scopes: Vec<String>) -> Self {
+        self.scopes = scopes;
+        self
+    }
+
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+}
+
+impl TokenSource for ClientCredentials {
+    fn token(&self) -> Result<String, failure::Error> {
+        self.cache.get(|| {
+            let scope = self.scopes.join(" ");
+            let mut params = vec![("grant_type", "client_credentials")];
+            if !scope.is_empty() {
+                params.push(("scope", scope.as_str()));
+            }
+            request_token(
+                &self.client,
+                &self.endpoint.url(&self.client)?,
+                &self.client_id,
+                Some(&self.client_secret),
+                &params,
+            )
+        })
+    }
+
+    fn invalidate(&self) {
+        self.cache.clear()
+    }
+}
+
+/// The OAuth 2.0 refresh token flow, trading a long-lived refresh token obtained elsewhere for
+/// access tokens. A new refresh token issued along with an access token replaces the old one.
+pub struct RefreshToken {
+    endpoint: Endpoint,
+    client_id: String,
+    client_secret: Option<String>,
+    refresh_token: Mutex<String>,
+    client: reqwest::Client,
+    cache: Cache,
+}
+
+impl RefreshToken {
+    pub fn new(
+        token_url: String,
+        client_id: String,
+        client_secret: Option<String>,
+        refresh_token: String,
+    ) -> Self {
+        RefreshToken {
+            endpoint: Endpoint::Token(token_url),
+            client_id,
+            client_secret,
+            refresh_token: Mutex::new(refresh_token),
+            client: reqwest::Client::new(),
+            cache: Cache::default(),
+        }
+    }
+
+    /// Reads the token endpoint from the OpenID Connect discovery document at
+    /// `openid_connect_url` on first use
+    pub fn discover(
+        openid_connect_url: String,
+        client_id: String,
+        client_secret: Option<String>,
+        refresh_token: String,
+    ) -> Self {
+        RefreshToken {
+            endpoint: Endpoint::discover(openid_connect_url),
+            ..RefreshToken::new(String::new(), client_id, client_secret, refresh_token)
+        }
+    }
+
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+}
+
+impl TokenSource for RefreshToken {
+    fn token(&self) -> Result<String, failure::Error> {
+        self.cache.get(|| {
+            let mut refresh_token = self
+                .refresh_token
+                .lock()
+                .map_err(|_| failure::format_err!("refresh token lock poisoned"))?;
+            let response = request_token(
+                &self.client,
+                &self.endpoint.url(&self.client)?,
+                &self.client_id,
+                self.client_secret.as_deref(),
+                &[
+                    ("grant_type", "refresh_token"),
+                    ("refresh_token", refresh_token.as_str()),
+                ],
+            )?;
+            if let Some(rotated) = response.refresh_token.as_ref() {
+                *refresh_token = rotated.clone();
+            }
+            Ok(response)
+        })
+    }
+
+    fn invalidate(&self) {
+        self.cache.clear()
+    }
+}
+
+/// Where tokens are requested, either given or found through OpenID Connect discovery
+enum Endpoint {
+    Token(String),
+    Discover(String, Mutex<Option<String>>),
+}
+
+impl Endpoint {
+    fn discover(openid_connect_url: String) -> Self {
+        Endpoint::Discover(openid_connect_url, Mutex::new(None))
+    }
+
+    fn url(&self, client: &reqwest::Client) -> Result<String, failure::Error> {
+        match self {
+            Endpoint::Token(url) => Ok(url.clone()),
+            Endpoint::Discover(openid_connect_url, token_url) => {
+                let mut token_url = token_url
+                    .lock()
+                    .map_err(|_| failure::format_err!("token endpoint lock poisoned"))?;
+                if let Some(url) = token_url.as_ref() {
+                    return Ok(url.clone());
+                }
+                let mut response = client.get(openid_connect_url.as_str()).send()?;
+                if !response.status().is_success() {
+                    return Err(failure::format_err!(
+                        "OpenID Connect discovery at {} failed with {}",
+                        openid_connect_url,
+                        response.status()
+                    ));
+                }
+                let discovery: Discovery = response.json()?;
+                *token_url = Some(discovery.token_endpoint.clone());
+                Ok(discovery.token_endpoint)
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Discovery {
+    token_endpoint: String,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<u64>,
+    refresh_token: Option<String>,
+}
+
+/// Asks the token endpoint for a token. A client with a secret authenticates with `Basic`
+/// authentication, and a public client sends its id in the form.
+fn request_token(
+    client: &reqwest::Client,
+    url: &str,
+    client_id: &str,
+    client_secret: Option<&str>,
+    params: &[(&str, &str)],
+) -> Result<TokenResponse, failure::Error> {
+    let mut params = params.to_vec();
+    let request = match client_secret {
+        Some(client_secret) => client.post(url).basic_auth(client_id, Some(client_secret)),
+        None => {
+            params.push(("client_id", client_id));
+            client.post(url)
+        }
+    };
+    let mut response = request.form(&params).send()?;
+    if !response.status().is_success() {
+        return Err(failure::format_err!(
+            "token request to {} failed with {}: {}",
+            url,
+            response.status(),
+            response.text().unwrap_or_default()
+        ));
+    }
+    response.json().map_err(|e| e.into())
+}
+
+/// The last token fetched, kept until shortly before it expires. The lock is held while
+/// fetching, so concurrent requests wait for a single new token.
+#[derive(Default)]
+struct Cache {
+    token: Mutex<Option<(String, Option<Instant>)>>,
+}
+
+impl Cache {
+    // `Option::is_none_or` would need Rust 1.82
+    #[allow(clippy::unnecessary_map_or)]
+    fn get<F>(&self, fetch: F) -> Result<String, failure::Error>
+    where
+        F: FnOnce() -> Result<TokenResponse, failure::Error>,
+    {
+        let mut token = self
+            .token
+            .lock()
+            .map_err(|_| failure::format_err!("token cache lock poisoned"))?;
+        if let Some((access_token, expires)) = token.as_ref() {
+            if expires.map_or(true, |expires| Instant::now() < expires) {
+                return Ok(access_token.clone());
+            }
+        }
+        let response = fetch()?;
+        let expires = response.expires_in.map(|expires_in| {
+            Instant::now() + Duration::from_secs(expires_in.saturating_sub(EXPIRY_MARGIN))
+        });
+        *token = Some((response.access_token.clone(), expires));
+        Ok(response.access_token)
+    }
+
+    fn clear(&self) {
+        if let Ok(mut token) = self.token.lock() {
+            *token = None;
+        }
+    }
+}
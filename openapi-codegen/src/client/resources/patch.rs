@@ -0,0 +1,66 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+/// A single member of a JSON Merge Patch document (RFC 7396)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Patch<T> {
+    /// Left out of the document, so the target is unchanged
+    Absent,
+    /// Sent as `null`, so the target is cleared
+    Null,
+    /// Sent as the given value, so the target is replaced
+    Value(T),
+}
+
+impl<T> Patch<T> {
+    pub fn is_absent(&self) -> bool {
+        matches!(self, Patch::Absent)
+    }
+}
+
+// Deriving it with `#[default]` would need Rust 1.62
+#[allow(clippy::derivable_impls)]
+impl<T> Default for Patch<T> {
+    fn default() -> Self {
+        Patch::Absent
+    }
+}
+
+impl<T> From<Option<T>> for Patch<T> {
+    fn from(option: Option<T>) -> Self {
+        match option {
+            Some(value) => Patch::Value(value),
+            None => Patch::Null,
+        }
+    }
+}
+
+impl<T: Serialize> Serialize for Patch<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Patch::Value(value) => value.serialize(serializer),
+            _ => serializer.serialize_none(),
+        }
+    }
+}
+
+// Absent members never reach here, they are filled in by `#[serde(default)]`
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Patch<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Option::<T>::deserialize(deserializer).map(|option| option.into())
+    }
+}
+
+/// A JSON Patch document (RFC 6902)
+pub type JsonPatch = Vec<PatchOperation>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: Value },
+}
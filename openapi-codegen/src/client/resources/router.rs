@@ -0,0 +1,193 @@
+use futures::{Future, Stream};
+use hyper::header::{HeaderValue, CONTENT_TYPE};
+use hyper::http::request::Parts;
+use hyper::service::service_fn;
+use hyper::{Body, Method, Request, Response, Server, StatusCode, Uri};
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::sync::{mpsc, Arc};
+use std::thread;
+
+type Handle = Box<dyn Fn(&Parts, &[u8]) -> Response<Body> + Send + Sync>;
+type ErrorHandler = Box<dyn Fn(hyper::Error) + Send>;
+
+/// Dispatches incoming callback requests to the handlers given to its `with_` methods
+#[derive(Default)]
+pub struct Router {
+    routes: Vec<Route>,
+    error_handler: Option<ErrorHandler>,
+}
+
+struct Route {
+    method: Method,
+    pattern: &'static str,
+    handle: Handle,
+}
+
+impl Router {
+    pub fn new() -> Self {
+        Router::default()
+    }
+
+    pub(crate) fn with_route<F>(mut self, method: Method, pattern: &'static str, handle: F) -> Self
+    where
+        F: Fn(&Parts, &[u8]) -> Response<Body> + Send + Sync + 'static,
+    {
+        self.routes.push(Route {
+            method,
+            pattern,
+            handle: Box::new(handle),
+        });
+        self
+    }
+
+    /// Answers a request whose body has already been read, for embedding in another server
+    pub fn handle(&self, request: Request<Vec<u8>>) -> Response<Body> {
+        let (parts, body) = request.into_parts();
+        respond(&self.routes, &parts, &body)
+    }
+
+    /// Is given the error which stops a server started by `spawn`, as there is no caller left
+    /// to return it to
+    pub fn with_error_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(hyper::Error) + Send + 'static,
+    {
+        self.error_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Serves callbacks on `address` until the process exits or the server fails
+    pub fn serve(self, address: &SocketAddr) -> Result<(), failure::Error> {
+        let (_, server) = self.bind(address)?;
+        let (sender, receiver) = mpsc::channel();
+        hyper::rt::run(server.map_err(move |e| {
+            let _ = sender.send(e);
+        }));
+        match receiver.try_recv() {
+            Ok(e) => Err(e.into()),
+            Err(_) => Ok(()),
+        }
+    }
+
+    /// Serves callbacks on a free local port from a background thread, returning the address
+    /// to build the callback URL from
+    pub fn spawn(mut self) -> Result<SocketAddr, failure::Error> {
+        let error_handler = self.error_handler.take();
+        let (address, server) = self.bind(&([127, 0, 0, 1], 0).into())?;
+        thread::spawn(move || {
+            hyper::rt::run(server.map_err(move |e| {
+                if let Some(error_handler) = error_handler {
+                    error_handler(e);
+                }
+            }))
+        });
+        Ok(address)
+    }
+
+    fn bind(
+        self,
+        address: &SocketAddr,
+    ) -> Result<
+        (
+            SocketAddr,
+            impl Future<Item = (), Error = hyper::Error> + Send,
+        ),
+        failure::Error,
+    > {
+        let routes = Arc::new(self.routes);
+        let server = Server::try_bind(address)?.serve(move || {
+            let routes = routes.clone();
+            service_fn(move |request: Request<Body>| {
+                let routes = routes.clone();
+                let (parts, body) = request.into_parts();
+                body.concat2()
+                    .map(move |body| respond(&routes, &parts, &body))
+            })
+        });
+        Ok((server.local_addr(), server))
+    }
+}
+
+/// Hands a request to the first route it fits, in the order they were added
+fn respond(routes: &[Route], parts: &Parts, body: &[u8]) -> Response<Body> {
+    match routes.iter().find(|route| {
+        route.method == parts.method && matches(route.pattern, target(route.pattern, &parts.uri))
+    }) {
+        Some(route) => (route.handle)(parts, body),
+        None => error(404, "no callback is routed here"),
+    }
+}
+
+/// The part of a request's URI a pattern is matched against: its path, and query string too
+/// when the pattern has one
+fn target<'a>(pattern: &str, uri: &'a Uri) -> &'a str {
+    match uri.path_and_query() {
+        Some(path_and_query) if pattern.contains('?') => path_and_query.as_str(),
+        _ => uri.path(),
+    }
+}
+
+/// Whether a URI target fits a pattern in which each `*` stands for any text
+fn matches(pattern: &str, path: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    if !path.starts_with(first) {
+        return false;
+    }
+    let mut rest = &path[first.len()..];
+    let parts = parts.collect::<Vec<&str>>();
+    match parts.split_last() {
+        None => rest.is_empty(),
+        Some((last, middle)) => {
+            for part in middle {
+                match rest.find(part) {
+                    Some(index) => rest = &rest[index + part.len()..],
+                    None => return false,
+                }
+            }
+            rest.ends_with(last)
+        }
+    }
+}
+
+/// An optional JSON body, where an empty one is `None`
+#[allow(dead_code)]
+pub(crate) fn optional_json<'de, T: Deserialize<'de>>(
+    body: &'de [u8],
+) -> Result<Option<T>, serde_json::Error> {
+    if body.is_empty() {
+        Ok(None)
+    } else {
+        serde_json::from_slice(body).map(Some)
+    }
+}
+
+#[allow(dead_code)]
+pub(crate) fn json<T: Serialize>(status: u16, body: &T) -> Response<Body> {
+    match serde_json::to_vec(body) {
+        Ok(body) => bytes(status, Some("application/json"), body),
+        Err(e) => error(500, &e.to_string()),
+    }
+}
+
+pub(crate) fn bytes(
+    status: u16,
+    content_type: Option<&'static str>,
+    body: Vec<u8>,
+) -> Response<Body> {
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() =
+        StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    if let Some(content_type) = content_type {
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+    }
+    response
+}
+
+/// A plain text error, for requests which never reach a handler or which it failed
+pub(crate) fn error(status: u16, message: &str) -> Response<Body> {
+    bytes(status, Some("text/plain"), message.as_bytes().to_vec())
+}
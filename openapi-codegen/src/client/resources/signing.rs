@@ -0,0 +1,125 @@
+use hmac::{Hmac, Mac};
+use hyper::header::{HeaderName, HeaderValue};
+use sha2::{Digest, Sha256};
+
+use super::configuration::Signer;
+
+/// Signs the canonical form of each request with a shared secret, sending
+/// `{prefix} KeyId={key_id}, SignedHeaders={headers}, Signature={hex}` in a header
+pub struct HmacSha256 {
+    key_id: String,
+    secret: Vec<u8>,
+    header: String,
+    prefix: String,
+    signed_headers: Vec<String>,
+}
+
+impl HmacSha256 {
+    pub fn new(key_id: String, secret: Vec<u8>) -> Self {
+        HmacSha256 {
+            key_id,
+            secret,
+            header: "Authorization".to_owned(),
+            prefix: "HMAC-SHA256".to_owned(),
+            signed_headers: vec!["host".to_owned()],
+        }
+    }
+
+    /// The header carrying the signature, `Authorization` by default
+    pub fn with_header(mut self, header: String) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// What the signature starts with, `HMAC-SHA256` by default
+    pub fn with_prefix(mut self, prefix: String) -> Self {
+        self.prefix = prefix;
+        self
+    }
+
+    /// The headers covered by the signature, only `host` by default
+    pub fn with_signed_headers(mut self, signed_headers: Vec<String>) -> Self {
+        self.signed_headers = signed_headers
+            .into_iter()
+            .map(|header| header.to_lowercase())
+            .collect();
+        self
+    }
+}
+
+impl Signer for HmacSha256 {
+    fn sign(
+        &self,
+        request: &mut reqwest::Request,
+        body: Option<&[u8]>,
+    ) -> Result<(), failure::Error> {
+        let mut mac = Hmac::<Sha256>::new_varkey(&self.secret)
+            .map_err(|_| failure::format_err!("invalid HMAC-SHA256 key"))?;
+        mac.input(canonical_request(request, &self.signed_headers, body).as_bytes());
+        let signature = format!(
+            "{} KeyId={}, SignedHeaders={}, Signature={}",
+            self.prefix,
+            self.key_id,
+            self.signed_headers.join(";"),
+            hex(&mac.result().code())
+        );
+        request.headers_mut().insert(
+            HeaderName::from_bytes(self.header.as_bytes())?,
+            HeaderValue::from_str(&signature)?,
+        );
+        Ok(())
+    }
+}
+
+/// The text a signature covers, one part per line: the method, the path, the query pairs in
+/// sorted order, a `name:value` line for each signed header, the signed header names joined
+/// with `;`, and the hex SHA-256 of the body, or `UNSIGNED-PAYLOAD` for a streamed one.
+/// Header names are lowercase, and `host` is read from the URL when the request has none.
+pub fn canonical_request(
+    request: &reqwest::Request,
+    signed_headers: &[String],
+    body: Option<&[u8]>,
+) -> String {
+    let url = request.url();
+
+    let mut query = url
+        .query()
+        .unwrap_or("")
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .collect::<Vec<&str>>();
+    query.sort();
+
+    let mut lines = vec![
+        request.method().as_str().to_owned(),
+        url.path().to_owned(),
+        query.join("&"),
+    ];
+    for name in signed_headers {
+        let values = request
+            .headers()
+            .get_all(name.as_str())
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .map(str::trim)
+            .collect::<Vec<&str>>();
+        let value = match (values.is_empty(), name.as_str()) {
+            (true, "host") => match url.port() {
+                Some(port) => format!("{}:{}", url.host_str().unwrap_or(""), port),
+                None => url.host_str().unwrap_or("").to_owned(),
+            },
+            _ => values.join(","),
+        };
+        lines.push(format!("{}:{}", name, value));
+    }
+    lines.push(signed_headers.join(";"));
+    lines.push(match body {
+        Some(body) => hex(&Sha256::digest(body)),
+        None => "UNSIGNED-PAYLOAD".to_owned(),
+    });
+    lines.join("\n")
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
@@ -0,0 +1,229 @@
+use crate::RustSnakeIdentifier;
+use serde_derive::Serialize;
+use serde_yaml::Value;
+
+/// A security scheme, held in its own slot of the generated `Credentials`
+#[derive(Debug, Serialize)]
+pub struct SecurityScheme {
+    pub api_id: String,
+    pub snake_id: RustSnakeIdentifier,
+    pub r#type: String,
+    /// Where the credential goes, for the slot's documentation
+    pub description: String,
+    /// The expression turning the slot's `value` into a `Credential`
+    pub credential: String,
+    /// The built-in token flows or signers the scheme allows
+    pub helpers: Vec<Helper>,
+}
+
+/// A `with_{scheme}_{name}` helper filling a slot with a built-in `TokenSource` or `Signer`
+#[derive(Debug, Serialize)]
+pub struct Helper {
+    pub name: &'static str,
+    pub description: String,
+    pub arguments: &'static str,
+    /// The expression building the slot's value
+    pub value: String,
+}
+
+impl Helper {
+    /// Where tokens come from, for the helper's documentation
+    fn endpoint(constructor: &str, url: &str) -> String {
+        match constructor {
+            "discover" => format!("the token endpoint discovered at {}", url),
+            _ => url.to_owned(),
+        }
+    }
+
+    fn client_credentials(constructor: &str, url: &str) -> Self {
+        Helper {
+            name: "client_credentials",
+            description: format!(
+                "Fetches tokens from {} with the client credentials flow",
+                Helper::endpoint(constructor, url)
+            ),
+            arguments: "client_id: String, client_secret: String",
+            value: format!(
+                "Token::new(super::oauth::ClientCredentials::{}({:?}.to_owned(), client_id, client_secret))",
+                constructor, url
+            ),
+        }
+    }
+
+    fn refresh_token(constructor: &str, url: &str) -> Self {
+        Helper {
+            name: "refresh_token",
+            description: format!(
+                "Fetches tokens from {} with a refresh token",
+                Helper::endpoint(constructor, url)
+            ),
+            arguments: "client_id: String, client_secret: Option<String>, refresh_token: String",
+            value: format!(
+                "Token::new(super::oauth::RefreshToken::{}({:?}.to_owned(), client_id, client_secret, refresh_token))",
+                constructor, url
+            ),
+        }
+    }
+
+    /// Builds the bundled signer as the `x-signature` extension configures it. The signature
+    /// header defaults to the name of an API key sent in a header.
+    fn hmac_sha256(scheme: &Value, signature: &Value) -> Self {
+        let mut value = "super::signing::HmacSha256::new(key_id, secret)".to_string();
+        let header = signature["header"]
+            .as_str()
+            .or_else(|| match scheme["in"].as_str() {
+                Some("header") => scheme["name"].as_str(),
+                _ => None,
+            });
+        if let Some(header) = header {
+            value.push_str(&format!(".with_header({:?}.to_owned())", header));
+        }
+        if let Some(prefix) = signature["prefix"].as_str() {
+            value.push_str(&format!(".with_prefix({:?}.to_owned())", prefix));
+        }
+        if let Some(headers) = signature["signedHeaders"].as_sequence() {
+            let headers = headers
+                .iter()
+                .filter_map(Value::as_str)
+                .map(|header| format!("{:?}.to_owned()", header))
+                .collect::<Vec<String>>();
+            value.push_str(&format!(
+                ".with_signed_headers(vec![{}])",
+                headers.join(", ")
+            ));
+        }
+
+        Helper {
+            name: "hmac_sha256",
+            description: "Signs requests with HMAC-SHA256, naming `key_id` as the key".to_string(),
+            arguments: "key_id: String, secret: Vec<u8>",
+            value: format!("Signature::new({})", value),
+        }
+    }
+}
+
+impl SecurityScheme {
+    /// Reads a scheme from the spec, or gives `None` for one with no credential to send
+    pub fn new(name: &str, scheme: &Value) -> Option<Self> {
+        let mut helpers = vec![];
+        let (r#type, description, credential) = match scheme["type"].as_str()? {
+            // A signature replaces whatever credential the scheme would otherwise send
+            _ if scheme["x-signature"].as_mapping().is_some() => {
+                let signature = &scheme["x-signature"];
+                let algorithm = signature["algorithm"].as_str().unwrap_or("hmac-sha256");
+                if algorithm.eq_ignore_ascii_case("hmac-sha256") {
+                    helpers.push(Helper::hmac_sha256(scheme, signature));
+                }
+                (
+                    "Signature",
+                    format!("A `{}` signer, which signs requests once they are built", algorithm),
+                    "Credential::Sign(value.clone())".to_string(),
+                )
+            }
+            "apiKey" => {
+                let key = scheme["name"].as_str()?;
+                let (location, credential) = match scheme["in"].as_str()? {
+                    "header" => ("header", "Header"),
+                    "query" => ("query parameter", "Query"),
+                    "cookie" => ("cookie", "Cookie"),
+                    _ => return None,
+                };
+                (
+                    "String",
+                    format!("An API key, sent as the `{}` {}", key, location),
+                    format!("Credential::{}({:?}, value.clone())", credential, key),
+                )
+            }
+            "http" => match scheme["scheme"].as_str()?.to_lowercase().as_str() {
+                "basic" => (
+                    "BasicAuth",
+                    "A username and password, sent with `Basic` authentication".to_string(),
+                    "Credential::Basic(value.0.clone(), value.1.clone())".to_string(),
+                ),
+                "bearer" => (
+                    "String",
+                    "A token, sent with `Bearer` authentication".to_string(),
+                    "Credential::Bearer(value.clone())".to_string(),
+                ),
+                // Any other scheme's credentials follow its name in the `Authorization` header
+                scheme => (
+                    "String",
+                    format!("Credentials sent with `{}` authentication", scheme),
+                    format!(
+                        "Credential::Header(\"Authorization\", format!(\"{{}} {{}}\", {:?}, value))",
+                        scheme
+                    ),
+                ),
+            },
+            "oauth2" => {
+                let flow = &scheme["flows"];
+                if let Some(url) = flow["clientCredentials"]["tokenUrl"].as_str() {
+                    helpers.push(Helper::client_credentials("new", url));
+                }
+                // Only the flows involving the user hand out refresh tokens
+                let refresh_url = ["authorizationCode", "password", "implicit"]
+                    .iter()
+                    .map(|name| &flow[*name])
+                    .filter_map(|flow| flow["refreshUrl"].as_str().or(flow["tokenUrl"].as_str()))
+                    .next();
+                if let Some(url) = refresh_url {
+                    helpers.push(Helper::refresh_token("new", url));
+                }
+                (
+                    "Token",
+                    "An OAuth 2.0 access token source, sent with `Bearer` authentication"
+                        .to_string(),
+                    "Credential::Token(value.clone())".to_string(),
+                )
+            }
+            "openIdConnect" => {
+                if let Some(url) = scheme["openIdConnectUrl"].as_str() {
+                    helpers.push(Helper::client_credentials("discover", url));
+                    helpers.push(Helper::refresh_token("discover", url));
+                }
+                (
+                    "Token",
+                    "An OpenID Connect access token source, sent with `Bearer` authentication"
+                        .to_string(),
+                    "Credential::Token(value.clone())".to_string(),
+                )
+            }
+            _ => return None,
+        };
+
+        Some(SecurityScheme {
+            api_id: name.to_owned(),
+            snake_id: name.to_owned().into(),
+            r#type: r#type.to_owned(),
+            description,
+            credential,
+            helpers,
+        })
+    }
+}
+
+/// The alternative requirements of a `security` list, as a `&[&[&str]]` of scheme names
+pub fn security_requirements(security: &Value) -> String {
+    let requirements = security
+        .as_sequence()
+        .map(|requirements| {
+            requirements
+                .iter()
+                .map(|requirement| {
+                    let schemes = requirement
+                        .as_mapping()
+                        .map(|schemes| {
+                            schemes
+                                .iter()
+                                .filter_map(|(scheme, _)| scheme.as_str())
+                                .map(|scheme| format!("{:?}", scheme))
+                                .collect::<Vec<String>>()
+                        })
+                        .unwrap_or_default();
+                    format!("&[{}]", schemes.join(", "))
+                })
+                .collect::<Vec<String>>()
+        })
+        .unwrap_or_default();
+    format!("&[{}]", requirements.join(", "))
+}
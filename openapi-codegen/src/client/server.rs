@@ -0,0 +1,228 @@
+use crate::{RustPascalIdentifier, RustSnakeIdentifier};
+use serde_derive::Serialize;
+use serde_yaml::Value;
+
+/// A variant of the generated `Server` enum, built from a `servers` entry
+#[derive(Debug, Serialize)]
+pub struct Server {
+    pub pascal_id: RustPascalIdentifier,
+    pub snake_id: RustSnakeIdentifier,
+    pub url: String,
+    pub description: Option<String>,
+    pub variables: Vec<ServerVariable>,
+    /// The variant's fields, as bound when matching it
+    pub binding: String,
+    /// The expression giving the URL, with each variable bound by `binding` substituted
+    pub expression: String,
+    /// The variant with each variable at its default
+    pub default: String,
+}
+
+impl Server {
+    /// Reads a `servers` entry, naming the variant after its `x-name` extension or its host.
+    /// `taken` holds the names of the variants so far, to keep each one unique.
+    pub fn new(server: &Value, taken: &[&RustPascalIdentifier]) -> Option<Self> {
+        let url = server["url"].as_str()?.to_owned();
+        let name = server["x-name"]
+            .as_str()
+            .map(str::to_owned)
+            .unwrap_or_else(|| host(&url));
+        let mut pascal_id: RustPascalIdentifier = name.clone().into();
+        let mut index = 1;
+        while taken.contains(&&pascal_id) {
+            index += 1;
+            pascal_id = format!("{}_{}", name, index).into();
+        }
+
+        let variables = server["variables"]
+            .as_mapping()
+            .map(|variables| {
+                variables
+                    .iter()
+                    .filter_map(|(name, variable)| {
+                        ServerVariable::new(&pascal_id, name.as_str()?, variable)
+                    })
+                    .collect::<Vec<ServerVariable>>()
+            })
+            .unwrap_or_default();
+
+        let (format, arguments) = format_url(&url, &variables);
+        let expression = if arguments.is_empty() {
+            format!("{:?}.to_owned()", url)
+        } else {
+            format!("format!({:?}, {})", format, arguments.join(", "))
+        };
+        let (binding, default) = if variables.is_empty() {
+            (String::new(), format!("Server::{}", pascal_id))
+        } else {
+            (
+                format!(
+                    " {{ {} }}",
+                    variables
+                        .iter()
+                        .map(|variable| format!("r#{}", variable.snake_id))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ),
+                format!(
+                    "Server::{} {{ {} }}",
+                    pascal_id,
+                    variables
+                        .iter()
+                        .map(|variable| format!("r#{}: {}", variable.snake_id, variable.default))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ),
+            )
+        };
+
+        Some(Server {
+            snake_id: pascal_id.to_string().into(),
+            pascal_id,
+            url,
+            description: server["description"].as_str().map(one_line),
+            variables,
+            binding,
+            expression,
+            default,
+        })
+    }
+}
+
+/// A variable of a server URL, typed as an enum of its values when the spec lists them
+#[derive(Debug, Serialize)]
+pub struct ServerVariable {
+    pub api_id: String,
+    pub snake_id: RustSnakeIdentifier,
+    pub r#type: String,
+    pub description: String,
+    /// The expression giving the default value
+    pub default: String,
+    pub values: Vec<ServerValue>,
+}
+
+impl ServerVariable {
+    fn new(server: &RustPascalIdentifier, name: &str, variable: &Value) -> Option<Self> {
+        let default = variable["default"].as_str()?;
+        let values = variable["enum"]
+            .as_sequence()
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(ServerValue::new)
+                    .collect::<Vec<ServerValue>>()
+            })
+            .unwrap_or_default();
+
+        let (r#type, default) = if values.is_empty() {
+            ("String".to_owned(), format!("{:?}.to_owned()", default))
+        } else {
+            let r#type = RustPascalIdentifier::from(format!("{}_{}", server, name)).to_string();
+            let default = values
+                .iter()
+                .find(|value| value.value == default)
+                .unwrap_or(&values[0]);
+            let default = format!("{}::{}", r#type, default.pascal_id);
+            (r#type, default)
+        };
+
+        Some(ServerVariable {
+            api_id: name.to_owned(),
+            snake_id: name.to_owned().into(),
+            r#type,
+            description: variable["description"]
+                .as_str()
+                .map(one_line)
+                .unwrap_or_else(|| format!("The `{}` variable", name)),
+            default,
+            values,
+        })
+    }
+}
+
+/// A value listed in a variable's `enum`, as a variant of the variable's type
+#[derive(Debug, Serialize)]
+pub struct ServerValue {
+    pub pascal_id: RustPascalIdentifier,
+    pub value: String,
+    /// The value as a string literal
+    pub literal: String,
+}
+
+impl ServerValue {
+    fn new(value: &str) -> Self {
+        // Values such as ports are not identifiers by themselves
+        let name = match value.chars().next() {
+            Some(c) if c.is_ascii_alphabetic() => value.to_owned(),
+            _ => format!("v{}", value),
+        };
+        ServerValue {
+            pascal_id: name.into(),
+            value: value.to_owned(),
+            literal: format!("{:?}", value),
+        }
+    }
+}
+
+/// The host of a URL without its variables, or `relative` for a URL with none
+fn host(url: &str) -> String {
+    let host = match url.find("://") {
+        Some(scheme) => url[scheme + 3..].split('/').next().unwrap_or(""),
+        None => "",
+    };
+    let mut name = String::new();
+    let mut depth = 0;
+    for c in host.chars() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth > 0 => depth -= 1,
+            c if depth == 0 => name.push(c),
+            _ => {}
+        }
+    }
+    let name = name.trim_matches(|c: char| !c.is_ascii_alphanumeric());
+    if name.chars().next().is_some_and(|c| c.is_ascii_alphabetic()) {
+        name.to_owned()
+    } else {
+        "relative".to_owned()
+    }
+}
+
+/// The URL as a `format!` string, with a `{}` and an argument for each declared variable
+fn format_url(url: &str, variables: &[ServerVariable]) -> (String, Vec<String>) {
+    let mut format = String::new();
+    let mut arguments = vec![];
+    let mut rest = url;
+    while let Some(start) = rest.find('{') {
+        format.push_str(&escape(&rest[..start]));
+        match rest[start..].find('}') {
+            Some(end) => {
+                let name = &rest[start + 1..start + end];
+                match variables.iter().find(|variable| variable.api_id == name) {
+                    Some(variable) => {
+                        format.push_str("{}");
+                        arguments.push(format!("r#{}", variable.snake_id));
+                    }
+                    None => format.push_str(&escape(&rest[start..=start + end])),
+                }
+                rest = &rest[start + end + 1..];
+            }
+            None => {
+                format.push_str(&escape(&rest[start..]));
+                rest = "";
+            }
+        }
+    }
+    format.push_str(&escape(rest));
+    (format, arguments)
+}
+
+/// A description fit for a single line of documentation
+fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('{', "{{").replace('}', "}}")
+}
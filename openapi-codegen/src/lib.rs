@@ -1,6 +1,6 @@
 pub mod client;
 
-pub use client::client;
+pub use client::{client, client_with_options, Grouping, Options, TagPlacement};
 use heck::{CamelCase, SnakeCase};
 use lazy_static::lazy_static;
 use openapiv3::ParameterData;
@@ -11,6 +11,7 @@ use openapiv3::{IntegerFormat, NumberFormat, ReferenceOr, StringFormat, VariantO
 use regex::Regex;
 use serde_derive::Serialize;
 use std::borrow::Borrow;
+use std::collections::HashSet;
 use std::fmt;
 
 lazy_static! {
@@ -38,8 +39,12 @@ lazy_static! {
     static ref INVALID_PATTERNS: Regex = Regex::new(r"[^a-zA-Z0-9_]").unwrap();
 }
 
+lazy_static! {
+    static ref TYPE_NAMES: Regex = Regex::new(r"[a-zA-Z_][a-zA-Z0-9_]*").unwrap();
+}
+
 /// These are potentically keywords, so should be prefixed with r# for safety
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct RustSnakeIdentifier(String);
 
 impl From<String> for RustSnakeIdentifier {
@@ -61,7 +66,7 @@ impl fmt::Display for RustSnakeIdentifier {
 }
 
 /// These are potentically keywords, so should be prefixed with r# for safety
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct RustPascalIdentifier(String);
 
 impl From<String> for RustPascalIdentifier {
@@ -82,7 +87,7 @@ impl fmt::Display for RustPascalIdentifier {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct RustType(String);
 
 impl From<&SchemaVariant> for RustType {
@@ -128,7 +133,7 @@ impl From<&ReferenceOr<Box<SchemaV3>>> for RustType {
                     .trim_start_matches("#/components/schemas/")
                     .trim_start_matches("#/components/requestBodies/")
                     .to_camel_case();
-                RustType(format!("{}", type_name))
+                RustType(type_name)
             }
             ReferenceOr::Item(schema) => match schema.borrow() {
                 SchemaV3::Schema(schema_variant) => {
@@ -160,7 +165,7 @@ impl From<&ReferenceOr<SchemaV3>> for RustType {
                     .trim_start_matches("#/components/schemas/")
                     .trim_start_matches("#/components/requestBodies/")
                     .to_camel_case();
-                RustType(format!("{}", type_name))
+                RustType(type_name)
             }
             ReferenceOr::Item(schema) => match schema {
                 SchemaV3::Schema(schema_variant) => {
@@ -187,7 +192,17 @@ impl From<&ReferenceOr<SchemaV3>> for RustType {
 impl From<&ParameterData> for RustType {
     fn from(parameter_data: &ParameterData) -> RustType {
         match &parameter_data.format {
-            openapiv3::ParameterSchemaOrContent::Content(_) => unimplemented!(),
+            // A content parameter has exactly one media type
+            openapiv3::ParameterSchemaOrContent::Content(content) => {
+                match content
+                    .values()
+                    .next()
+                    .and_then(|mediatype| mediatype.schema.as_ref())
+                {
+                    Some(reference_or_schema) => reference_or_schema.into(),
+                    None => RustType("Value".into()),
+                }
+            }
             openapiv3::ParameterSchemaOrContent::Schema(ref reference_or_schema) => {
                 reference_or_schema.into()
             }
@@ -203,7 +218,7 @@ impl From<&ReferenceOr<RequestBody>> for RustType {
                     .trim_start_matches("#/components/schemas/")
                     .trim_start_matches("#/components/requestBodies/")
                     .to_camel_case();
-                RustType(format!("{}", type_name))
+                RustType(type_name)
             }
             ReferenceOr::Item(requestbody) => match requestbody.content.get("application/json") {
                 Some(mediatype) => match mediatype.schema {
@@ -222,10 +237,65 @@ impl From<&ReferenceOr<RequestBody>> for RustType {
 impl RustType {
     pub fn borrowed(&self) -> RustType {
         RustType(match self.0.as_str() {
-            "String" => "&str".into(),
+            "String" | "Cow<'a, str>" => "&str".into(),
             x => format!("&{}", x),
         })
     }
+
+    /// The names of every type this type is built from
+    pub fn names(&self) -> Vec<&str> {
+        TYPE_NAMES.find_iter(&self.0).map(|m| m.as_str()).collect()
+    }
+
+    /// The zero-copy form of a model field, where strings become `Cow<'a, str>` and
+    /// models in `borrowing` take the `'a` lifetime
+    pub fn cow(&self, borrowing: &HashSet<String>) -> RustType {
+        RustType(
+            TYPE_NAMES
+                .replace_all(&self.0, |captures: &regex::Captures| match &captures[0] {
+                    "String" => "Cow<'a, str>".to_string(),
+                    name if borrowing.contains(name) => format!("{}<'a>", name),
+                    name => name.to_string(),
+                })
+                .into_owned(),
+        )
+    }
+
+    /// Gives every model in `borrowing` the `lifetime`, leaving strings owned
+    pub fn with_lifetime(&self, lifetime: &str, borrowing: &HashSet<String>) -> RustType {
+        RustType(
+            TYPE_NAMES
+                .replace_all(&self.0, |captures: &regex::Captures| match &captures[0] {
+                    name if borrowing.contains(name) => format!("{}<{}>", name, lifetime),
+                    name => name.to_string(),
+                })
+                .into_owned(),
+        )
+    }
+
+    /// An expression converting `expression`, the zero-copy form of this type, into its `'static` form
+    pub fn owned_expression(&self, expression: &str, borrowing: &HashSet<String>) -> String {
+        match self.0.find('<') {
+            Some(index) if self.0.ends_with('>') => {
+                let inner = RustType(self.0[index + 1..self.0.len() - 1].to_string());
+                let converted = inner.owned_expression("x", borrowing);
+                match &self.0[..index] {
+                    _ if converted == "x" => expression.to_string(),
+                    "Vec" => format!(
+                        "{}.into_iter().map(|x| {}).collect()",
+                        expression, converted
+                    ),
+                    "Option" => format!("{}.map(|x| {})", expression, converted),
+                    _ => expression.to_string(),
+                }
+            }
+            _ => match self.0.as_str() {
+                "String" => format!("Cow::Owned({}.into_owned())", expression),
+                name if borrowing.contains(name) => format!("{}.into_owned()", expression),
+                _ => expression.to_string(),
+            },
+        }
+    }
 }
 
 impl fmt::Display for RustType {
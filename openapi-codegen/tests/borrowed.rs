@@ -0,0 +1,62 @@
+mod common;
+
+use common::generate;
+use openapi_codegen::Options;
+
+const BORROWED: &str = include_str!("specs/borrowed.yaml");
+
+fn borrowed() -> Options {
+    Options {
+        borrowed_models: true,
+        ..Options::default()
+    }
+}
+
+#[test]
+fn models_holding_strings_borrow_them() {
+    let client = generate("borrowed_strings", BORROWED, borrowed()).unwrap();
+    let pet = client.file("models/pet.rs");
+
+    assert!(pet.contains("pub struct r#Pet<'a> {"));
+    assert!(pet.contains("r#name: Cow<'a, str>,"));
+    assert!(pet.contains("pub fn into_owned(self) -> r#Pet<'static> {"));
+    // Only the helpers the fields deserialize with are generated
+    let borrow = client.file("models/borrow.rs");
+    assert!(borrow.contains("pub fn option_str<"));
+    assert!(!borrow.contains("pub fn vec_str<"));
+}
+
+#[test]
+fn models_holding_borrowing_models_borrow_too() {
+    let client = generate("borrowed_nested", BORROWED, borrowed()).unwrap();
+
+    assert!(client
+        .file("models/pet.rs")
+        .contains("r#owner: Option<Owner<'a>>,"));
+    assert!(client
+        .file("models/pets.rs")
+        .contains("pub struct r#Pets<'a>("));
+    assert!(client
+        .file("models/counts.rs")
+        .contains("pub struct r#Counts {"));
+}
+
+#[test]
+fn borrowed_responses_are_read_into_a_buffer() {
+    let client = generate("borrowed_responses", BORROWED, borrowed()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains(
+        "pub fn r#list_pets<'b>(\n        &self,\n        buffer: &'b mut Vec<u8>,\n    ) -> Result<Pets<'b>,"
+    ));
+    assert!(api.contains("r#body: Pet<'_>,"));
+    assert!(api.contains("pub fn r#get_counts(\n        &self,\n    ) -> Result<Counts,"));
+}
+
+#[test]
+fn models_are_owned_by_default() {
+    let client = generate("borrowed_default", BORROWED, Options::default()).unwrap();
+
+    assert!(client.file("models/pet.rs").contains("r#name: String,"));
+    assert!(!client.exists("models/borrow.rs"));
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use common::generate;
+use openapi_codegen::Options;
+
+const CALLBACKS: &str = include_str!("specs/callbacks.yaml");
+
+#[test]
+fn referenced_callback_responses_are_resolved() {
+    let client = generate("callbacks_references", CALLBACKS, Options::default()).unwrap();
+    let callbacks = client.file("callbacks/mod.rs");
+
+    assert!(callbacks.contains("pub enum OnEventPost1Response {\n    Status202(String),\n}"));
+}
+
+#[test]
+fn callback_routes_keep_the_query_string() {
+    let client = generate("callbacks_query", CALLBACKS, Options::default()).unwrap();
+    let callbacks = client.file("callbacks/mod.rs");
+
+    assert!(callbacks.contains(".with_route(hyper::Method::POST, \"*/events?kind=created\", {"));
+    assert!(callbacks.contains(".with_route(hyper::Method::POST, \"*/events?kind=deleted\", {"));
+}
+
+#[test]
+fn a_missing_callback_response_is_an_error() {
+    let spec = CALLBACKS.replace(
+        "#/components/responses/Accepted",
+        "#/components/responses/Gone",
+    );
+    let error = generate("callbacks_missing_response", &spec, Options::default())
+        .err()
+        .unwrap();
+
+    assert!(
+        error.to_string().contains("#/components/responses/Gone"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn callbacks_which_cannot_be_told_apart_are_routed_in_order() {
+    let spec = CALLBACKS
+        .replace("/events?kind=created", "")
+        .replace("/events?kind=deleted", "/events");
+    let client = generate("callbacks_ambiguous", &spec, Options::default()).unwrap();
+    let callbacks = client.file("callbacks/mod.rs");
+
+    let wildcard = callbacks
+        .find(".with_route(hyper::Method::POST, \"*\", {")
+        .unwrap();
+    let events = callbacks
+        .find(".with_route(hyper::Method::POST, \"*/events\", {")
+        .unwrap();
+    assert!(wildcard < events);
+}
@@ -0,0 +1,41 @@
+use openapi_codegen::{client_with_options, Options};
+use std::fs;
+use std::path::PathBuf;
+
+/// A client generated into a directory of its own
+pub struct Generated {
+    dir: PathBuf,
+}
+
+impl Generated {
+    /// The generated file at `path`, such as `apis/mod.rs`
+    pub fn file(&self, path: &str) -> String {
+        fs::read_to_string(self.dir.join("client").join(path))
+            .unwrap_or_else(|e| panic!("reading {}: {}", path, e))
+    }
+
+    /// Whether the generator wrote `path`; not every test binary asks
+    #[allow(dead_code)]
+    pub fn exists(&self, path: &str) -> bool {
+        self.dir.join("client").join(path).exists()
+    }
+}
+
+/// Generates a client for `spec` into a fresh directory named after the test
+pub fn generate(name: &str, spec: &str, options: Options) -> Result<Generated, failure::Error> {
+    let dir = std::env::temp_dir()
+        .join("openapi-codegen-tests")
+        .join(name);
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?;
+    }
+    fs::create_dir_all(&dir)?;
+    let spec_path = dir.join("openapi.yaml");
+    fs::write(&spec_path, spec)?;
+    client_with_options(
+        spec_path.to_str().unwrap(),
+        dir.join("client").to_str().unwrap(),
+        options,
+    )?;
+    Ok(Generated { dir })
+}
@@ -0,0 +1,124 @@
+mod common;
+
+use common::generate;
+use openapi_codegen::Options;
+
+const FORMS: &str = include_str!("specs/forms.yaml");
+
+/// A spec with a single `POST /forms` operation taking `body`, the YAML of a request body
+fn body_spec(body: &str) -> String {
+    format!(
+        r#"openapi: "3.0.0"
+info:
+  version: 1.0.0
+  title: Body
+paths:
+  /forms:
+    post:
+      operationId: send
+      requestBody:
+{}
+      responses:
+        '204':
+          description: Sent
+"#,
+        body.lines()
+            .map(|line| format!("        {}", line))
+            .collect::<Vec<String>>()
+            .join("\n")
+    )
+}
+
+#[test]
+fn multipart_arrays_are_sent_as_a_part_per_item() {
+    let client = generate("multipart_arrays", FORMS, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains("pub r#attachments: Option<Vec<FilePart>>,"));
+    assert!(api.contains("for value in self.r#attachments.into_iter().flatten() {"));
+    assert!(api.contains("for value in self.r#labels.into_iter().flatten() {"));
+    assert!(api.contains("for value in self.r#sizes.into_iter().flatten() {"));
+    // An array sent as JSON stays a single part
+    assert!(api.contains("for value in self.r#metadata.into_iter() {"));
+    assert!(api.contains("for value in std::iter::once(self.r#document) {"));
+}
+
+#[test]
+fn multipart_part_headers_become_fields() {
+    let client = generate("multipart_headers", FORMS, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains("pub r#document_x_checksum: Option<String>,"));
+    assert!(api.contains("Some(\"application/pdf\"),"));
+}
+
+#[test]
+fn urlencoded_arrays_are_split_by_style() {
+    let client = generate("urlencoded_arrays", FORMS, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains(".with_form_params(r#form.into_form_params()?)"));
+    assert!(api.contains("for value in self.r#tags.into_iter() {"));
+    assert!(api.contains("for value in std::iter::once(self.r#query) {"));
+    assert!(api.contains("_internal_style::Style::DeepObject,"));
+}
+
+#[test]
+fn a_form_without_a_schema_is_an_error() {
+    let spec = body_spec("content:\n  multipart/form-data: {}");
+    let error = generate("form_without_schema", &spec, Options::default())
+        .err()
+        .unwrap();
+
+    assert!(error.to_string().contains("has no schema"), "{}", error);
+}
+
+#[test]
+fn a_form_of_a_scalar_is_an_error() {
+    let spec = body_spec(
+        "content:\n  application/x-www-form-urlencoded:\n    schema:\n      type: string",
+    );
+    let error = generate("form_of_scalar", &spec, Options::default())
+        .err()
+        .unwrap();
+
+    assert!(
+        error.to_string().contains("needs an object schema"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn a_form_of_a_missing_schema_is_an_error() {
+    let spec = body_spec(
+        "content:\n  multipart/form-data:\n    schema:\n      $ref: \"#/components/schemas/Missing\"",
+    );
+    let error = generate("form_of_missing_schema", &spec, Options::default())
+        .err()
+        .unwrap();
+
+    assert!(error.to_string().contains("Missing"), "{}", error);
+}
+
+#[test]
+fn a_json_body_without_a_schema_is_an_error() {
+    let spec = body_spec("content:\n  application/json: {}");
+    let error = generate("json_without_schema", &spec, Options::default())
+        .err()
+        .unwrap();
+
+    assert!(error.to_string().contains("POST /forms"), "{}", error);
+}
+
+#[test]
+fn urlencoded_fields_keep_an_explicit_explode() {
+    let client = generate("urlencoded_explode", FORMS, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains("\"ids\",\n                &value,\n                _internal_style::Style::Form,\n                false,"));
+    assert!(api.contains("\"tags\",\n                &value,\n                _internal_style::Style::Form,\n                true,"));
+    assert!(api.contains(
+        "\"filter\",\n                &value,\n                _internal_style::Style::DeepObject,\n                true,"
+    ));
+}
@@ -0,0 +1,142 @@
+mod common;
+
+use common::generate;
+use openapi_codegen::{Grouping, Options, TagPlacement};
+
+const TAGS: &str = include_str!("specs/tags.yaml");
+
+#[test]
+fn operation_types_are_defined_by_the_first_tag() {
+    let client = generate("types_first_tag", TAGS, Options::default()).unwrap();
+
+    let pets = client.file("apis/pets_api.rs");
+    assert!(pets.contains("pub enum ListPetsError"));
+    assert!(pets.contains("pub enum ListPetsResponse"));
+    assert!(pets.contains("pub struct ListPetsHeaders"));
+    assert!(pets.contains("pub struct CreatePetForm"));
+
+    let store = client.file("apis/store_api.rs");
+    assert!(store.contains("pub fn r#list_pets("));
+    assert!(!store.contains("pub enum ListPetsError"));
+    assert!(store.contains("use super::{ListPetsError, ListPetsResponse, ListPetsHeaders};"));
+}
+
+#[test]
+fn nested_clients_import_operation_types_from_the_root() {
+    let options = Options {
+        grouping: Grouping::NestedTag,
+        ..Options::default()
+    };
+    let client = generate("types_nested", TAGS, options).unwrap();
+
+    let users = client.file("apis/admin/users_api.rs");
+    assert!(!users.contains("pub struct CreatePetForm"));
+    assert!(users.contains("use super::super::{CreatePetError, CreatePetForm};"));
+}
+
+#[test]
+fn primary_placement_defines_operation_types_once() {
+    let options = Options {
+        tag_placement: TagPlacement::Primary,
+        ..Options::default()
+    };
+    let client = generate("types_primary", TAGS, options).unwrap();
+
+    assert!(client
+        .file("apis/pets_api.rs")
+        .contains("pub enum ListPetsError"));
+    assert!(!client.file("apis/store_api.rs").contains("ListPets"));
+}
+
+#[test]
+fn params_structs_of_multi_tag_operations_are_shared() {
+    let options = Options {
+        params_structs: true,
+        ..Options::default()
+    };
+    let client = generate("types_params", TAGS, options).unwrap();
+
+    assert!(client
+        .file("apis/pets_api.rs")
+        .contains("pub struct ListPetsParams"));
+    let store = client.file("apis/store_api.rs");
+    assert!(!store.contains("pub struct ListPetsParams"));
+    assert!(store.contains(
+        "use super::{ListPetsError, ListPetsResponse, ListPetsHeaders, ListPetsParams};"
+    ));
+}
+
+#[test]
+fn the_root_client_gives_each_tag_client() {
+    let client = generate("grouping_tag", TAGS, Options::default()).unwrap();
+    let root = client.file("apis/client.rs");
+
+    assert!(root.contains("pub fn r#pets(&self) -> &super::PetsApiClient {"));
+    assert!(root.contains("pub fn r#admin_users(&self) -> &super::AdminUsersApiClient {"));
+    assert!(root.contains("r#store: super::StoreApiClient::shared(configuration.clone()),"));
+    assert!(!root.contains("impl Deref for Client"));
+}
+
+#[test]
+fn nested_tags_become_modules() {
+    let options = Options {
+        grouping: Grouping::NestedTag,
+        ..Options::default()
+    };
+    let client = generate("grouping_nested", TAGS, options).unwrap();
+
+    assert!(client.exists("apis/admin/users_api.rs"));
+    assert!(client
+        .file("apis/client.rs")
+        .contains("pub fn r#admin_users(&self) -> &super::r#admin::UsersApiClient {"));
+}
+
+#[test]
+fn path_segments_group_operations() {
+    let options = Options {
+        grouping: Grouping::PathSegment,
+        ..Options::default()
+    };
+    let client = generate("grouping_segment", TAGS, options).unwrap();
+
+    assert!(client
+        .file("apis/pets_api.rs")
+        .contains("pub fn r#list_pets("));
+    assert!(client
+        .file("apis/store_api.rs")
+        .contains("pub fn r#list_orders("));
+    assert!(client
+        .file("apis/health_api.rs")
+        .contains("pub fn r#health("));
+    assert!(!client.exists("apis/untagged_api.rs"));
+}
+
+#[test]
+fn path_segments_which_cannot_name_a_client_are_skipped() {
+    let options = Options {
+        grouping: Grouping::PathSegment,
+        ..Options::default()
+    };
+    let spec = TAGS.replace("  /health:", "  /2.0/health:");
+    let client = generate("grouping_segment_versioned", &spec, options).unwrap();
+
+    assert!(client
+        .file("apis/health_api.rs")
+        .contains("pub fn r#health("));
+}
+
+#[test]
+fn a_flat_client_dereferences_to_its_operations() {
+    let options = Options {
+        grouping: Grouping::Flat,
+        ..Options::default()
+    };
+    let client = generate("grouping_flat", TAGS, options).unwrap();
+
+    assert!(client
+        .file("apis/operations_api.rs")
+        .contains("pub fn r#health("));
+    assert!(client
+        .file("apis/client.rs")
+        .contains("impl Deref for Client {\n    type Target = super::OperationsApiClient;"));
+}
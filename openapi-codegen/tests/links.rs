@@ -0,0 +1,51 @@
+mod common;
+
+use common::generate;
+use openapi_codegen::Options;
+
+const LINKS: &str = include_str!("specs/links.yaml");
+
+#[test]
+fn links_fill_in_parameters_from_the_body() {
+    let client = generate("links_body", LINKS, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains("pub fn r#follow_repository(\n        &self,\n        response: &User,"));
+    assert!(api.contains("_internal_links::body_value(response, \"/username\")?"));
+    assert!(api.contains("Some(_internal_style::parse_text(\"full\")?)"));
+}
+
+#[test]
+fn links_leave_unfilled_required_parameters_to_the_caller() {
+    let client = generate("links_arguments", LINKS, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains("response: &User,\n        r#slug: String,\n    )"));
+    // Unfilled optional parameters are left unset
+    assert!(api.contains("_internal_links::body_value(response, \"/repository\")?, None)"));
+}
+
+#[test]
+fn links_of_the_same_name_are_told_apart_by_their_source() {
+    let client = generate("links_sources", LINKS, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains(
+        "pub fn r#follow_repository_from_get_team(\n        &self,\n        response: &Team,"
+    ));
+}
+
+#[test]
+fn links_build_params_structs() {
+    let options = Options {
+        params_structs: true,
+        ..Options::default()
+    };
+    let client = generate("links_params", LINKS, options).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains(
+        "let mut params = GetRepositoryParams::new(_internal_links::body_value(response, \"/username\")?, r#slug);"
+    ));
+    assert!(api.contains("params = params.with_format(value);"));
+}
@@ -0,0 +1,112 @@
+mod common;
+
+use common::generate;
+use openapi_codegen::Options;
+
+const MODELS: &str = include_str!("specs/models.yaml");
+
+#[test]
+fn fields_are_private_with_accessors_by_default() {
+    let client = generate("models_default", MODELS, Options::default()).unwrap();
+    let pet = client.file("models/pet.rs");
+
+    assert!(pet.contains("\n    r#name: String,"));
+    assert!(pet.contains("pub fn with_tag(mut self, r#tag: String) -> Self {"));
+    assert!(pet.contains("pub fn r#name(&self) -> &str {"));
+    assert!(!pet.contains("#[non_exhaustive]"));
+}
+
+#[test]
+fn public_fields_can_replace_the_accessors() {
+    let options = Options {
+        public_fields: true,
+        accessors: false,
+        ..Options::default()
+    };
+    let client = generate("models_public", MODELS, options).unwrap();
+    let pet = client.file("models/pet.rs");
+
+    assert!(pet.contains("pub r#name: String,"));
+    assert!(pet.contains("pub r#tag: Option<String>,"));
+    assert!(!pet.contains("pub fn with_tag("));
+    assert!(!pet.contains("pub fn r#name(&self)"));
+    assert!(client
+        .file("models/pets.rs")
+        .contains("pub struct r#Pets(pub Vec<Pet>);"));
+}
+
+#[test]
+fn models_without_public_fields_or_accessors_are_an_error() {
+    let options = Options {
+        accessors: false,
+        ..Options::default()
+    };
+
+    assert!(generate("models_inaccessible", MODELS, options).is_err());
+}
+
+#[test]
+fn structs_can_be_non_exhaustive() {
+    let options = Options {
+        public_fields: true,
+        non_exhaustive: true,
+        ..Options::default()
+    };
+    let client = generate("models_non_exhaustive", MODELS, options).unwrap();
+
+    assert!(client
+        .file("models/pet.rs")
+        .contains("#[non_exhaustive]\npub struct r#Pet {"));
+}
+
+#[test]
+fn enums_convert_to_and_from_their_wire_names() {
+    let client = generate("models_enum", MODELS, Options::default()).unwrap();
+    let status = client.file("models/status.rs");
+
+    assert!(status.contains("pub const ALL: &'static [r#Status] = &["));
+    assert!(status.contains("r#Status::r#Available => \"available\","));
+    assert!(status.contains("impl fmt::Display for r#Status {"));
+    assert!(status.contains("\"sold\" => Ok(r#Status::r#Sold),"));
+}
+
+#[test]
+fn scalar_newtypes_convert_to_and_from_strings() {
+    let client = generate("models_newtype_display", MODELS, Options::default()).unwrap();
+    let owner_id = client.file("models/owner_id.rs");
+
+    assert!(owner_id.contains("impl fmt::Display for r#OwnerId {"));
+    assert!(owner_id.contains("impl FromStr for r#OwnerId {"));
+}
+
+#[test]
+fn model_parameters_are_taken_as_their_model() {
+    let client = generate("models_parameters", MODELS, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains("r#status: Option<Status>,"));
+    assert!(api.contains("r#owner: Option<OwnerId>,"));
+}
+
+#[test]
+fn collection_newtypes_behave_like_their_collection() {
+    let client = generate("models_collection", MODELS, Options::default()).unwrap();
+    let pets = client.file("models/pets.rs");
+
+    assert!(pets.contains("#[serde(transparent)]\npub struct r#Pets(Vec<Pet>);"));
+    assert!(pets.contains("impl Deref for r#Pets {"));
+    assert!(pets.contains("impl From<Vec<Pet>> for r#Pets {"));
+    assert!(pets.contains("impl From<r#Pets> for Vec<Pet> {"));
+    assert!(pets.contains("impl<'i> IntoIterator for &'i r#Pets {"));
+    assert!(pets.contains("impl FromIterator<Pet> for r#Pets {"));
+}
+
+#[test]
+fn scalar_newtypes_dereference_to_their_value() {
+    let client = generate("models_scalar", MODELS, Options::default()).unwrap();
+    let owner_id = client.file("models/owner_id.rs");
+
+    assert!(owner_id.contains("impl DerefMut for r#OwnerId {"));
+    assert!(owner_id.contains("impl AsRef<str> for r#OwnerId {"));
+    assert!(!owner_id.contains("IntoIterator"));
+}
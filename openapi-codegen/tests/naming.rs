@@ -0,0 +1,101 @@
+mod common;
+
+use common::generate;
+use openapi_codegen::Options;
+
+const NAMING: &str = include_str!("specs/naming.yaml");
+const LINKS: &str = include_str!("specs/links.yaml");
+
+/// `spec` with a `GET` of `path` named `operation_id` in the `tag` client
+fn with_operation(spec: &str, path: &str, operation_id: &str, tag: &str) -> String {
+    spec.replacen(
+        "paths:\n",
+        &format!(
+            "paths:\n  {}:\n    get:\n      operationId: {}\n      tags:\n        - {}\n      responses:\n        '204':\n          description: Done\n",
+            path, operation_id, tag
+        ),
+        1,
+    )
+}
+
+fn error(name: &str, spec: &str) -> String {
+    generate(name, spec, Options::default())
+        .err()
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn operations_without_an_operation_id_are_named_from_their_path() {
+    let client = generate("naming_paths", NAMING, Options::default()).unwrap();
+    let api = client.file("apis/pets_api.rs");
+
+    assert!(api.contains("pub fn r#get_pets("));
+    assert!(api.contains("pub fn r#get_pets_pages<'c>("));
+    assert!(api.contains("pub fn r#get_pets_by_pet_id("));
+}
+
+#[test]
+fn a_repeated_tag_gives_one_method() {
+    let client = generate("naming_repeated_tag", NAMING, Options::default()).unwrap();
+    let api = client.file("apis/pets_api.rs");
+
+    assert_eq!(api.matches("pub fn r#get_pets(").count(), 1);
+}
+
+#[test]
+fn operations_with_the_same_name_in_a_client_are_an_error() {
+    let spec = with_operation(NAMING, "/animals", "getPetsByPetId", "pets");
+    let error = error("naming_same_method", &spec);
+
+    assert!(
+        error.contains("`get_pets_by_pet_id` in the `pets` client"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn an_operation_named_like_a_pages_method_is_an_error() {
+    let spec = with_operation(NAMING, "/animals", "getPetsPages", "pets");
+    let error = error("naming_pages", &spec);
+
+    assert!(error.contains("`get_pets_pages`"), "{}", error);
+}
+
+#[test]
+fn an_operation_named_like_a_headers_method_is_an_error() {
+    let spec = with_operation(NAMING, "/animals", "getPetsWithHeaders", "pets");
+    let error = error("naming_with_headers", &spec);
+
+    assert!(error.contains("`get_pets_with_headers`"), "{}", error);
+}
+
+#[test]
+fn an_operation_named_like_a_follow_method_is_an_error() {
+    let spec = with_operation(LINKS, "/follow", "followRepository", "untagged");
+    let error = error("naming_follow", &spec);
+
+    assert!(error.contains("`follow_repository`"), "{}", error);
+}
+
+#[test]
+fn an_operation_named_like_a_client_constructor_is_an_error() {
+    let spec = with_operation(NAMING, "/animals", "new", "pets");
+    let error = error("naming_new", &spec);
+
+    assert!(error.contains("`new`"), "{}", error);
+}
+
+#[test]
+fn clients_of_one_module_defining_the_same_type_are_an_error() {
+    let spec = with_operation(
+        &with_operation(NAMING, "/cats", "show_cat", "pets"),
+        "/store/cats",
+        "showCat",
+        "store",
+    );
+    let error = error("naming_types", &spec);
+
+    assert!(error.contains("`ShowCatError`"), "{}", error);
+}
@@ -0,0 +1,166 @@
+mod common;
+
+use common::generate;
+use openapi_codegen::Options;
+
+const OPERATIONS: &str = include_str!("specs/operations.yaml");
+
+#[test]
+fn response_headers_are_returned_with_the_body() {
+    let client = generate("operations_headers", OPERATIONS, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains(
+        "-> Result<super::Response<ShowPetResponse, ShowPetHeaders>, super::Error<ShowPetError>>"
+    ));
+    assert!(api.contains("headers: ShowPetHeaders::parse(&headers)?,"));
+}
+
+#[test]
+fn response_headers_are_optional_unless_every_success_requires_them() {
+    let client = generate(
+        "operations_required_headers",
+        OPERATIONS,
+        Options::default(),
+    )
+    .unwrap();
+    let api = client.file("apis/untagged_api.rs");
+    assert!(api.contains("pub r#x_rate_limit: Option<i32>,"));
+
+    let spec = OPERATIONS.replace(
+        "        '202':\n          description: Still being fetched\n",
+        "",
+    );
+    let client = generate("operations_required_header", &spec, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+    assert!(api.contains("pub r#x_rate_limit: i32,"));
+    assert!(api.contains("pub r#e_tag: Option<String>,"));
+}
+
+#[test]
+fn several_success_statuses_become_a_response_enum() {
+    let client = generate("operations_statuses", OPERATIONS, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains("pub enum ShowPetResponse {\n    Status200(Pet),\n    Status202,\n}"));
+    assert!(api.contains("body: ShowPetResponse::Status202,"));
+}
+
+#[test]
+fn error_statuses_become_an_error_enum() {
+    let client = generate("operations_errors", OPERATIONS, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains("pub enum ShowPetError {\n    Status404(Problem),\n    Default,\n}"));
+    assert!(api.contains("content: Some(ShowPetError::Status404(response.json()?)),"));
+    assert!(api.contains("content: Some(ShowPetError::Default),"));
+}
+
+#[test]
+fn referenced_responses_are_resolved() {
+    let client = generate("operations_referenced", OPERATIONS, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+    assert!(api.contains("pub enum DownloadPhotoError {\n    Status404(Problem),\n}"));
+
+    let spec = OPERATIONS.replace(
+        "$ref: \"#/components/responses/NotFound\"",
+        "$ref: \"#/components/responses/Missing\"",
+    );
+    let error = generate("operations_missing_response", &spec, Options::default())
+        .err()
+        .unwrap();
+    assert!(error
+        .to_string()
+        .contains("#/components/responses/Missing is not a response of the spec"));
+}
+
+#[test]
+fn head_options_and_trace_operations_are_generated() {
+    let client = generate("operations_methods", OPERATIONS, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains("hyper::Method::HEAD,"));
+    assert!(api.contains("hyper::Method::OPTIONS,"));
+    assert!(api.contains("hyper::Method::TRACE,"));
+}
+
+#[test]
+fn head_operations_return_the_response_headers() {
+    let client = generate("operations_head", OPERATIONS, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains(") -> Result<hyper::HeaderMap, super::Error<CheckPetError>> {"));
+    assert!(api.contains("200 => Ok(response.headers().clone()),"));
+}
+
+#[test]
+fn binary_bodies_are_sent_and_streamed_as_bytes() {
+    let client = generate("operations_binary", OPERATIONS, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains("r#body: Binary,"));
+    assert!(api.contains(".with_binary_body(r#body)"));
+    assert!(api.contains(") -> Result<ByteStream, super::Error<DownloadPhotoError>> {"));
+    assert!(api.contains("200 => Ok(_internal_binary::ByteStream::new(response)),"));
+}
+
+#[test]
+fn header_and_cookie_parameters_are_sent() {
+    let client = generate("operations_header_cookie", OPERATIONS, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains(
+        ".with_optional_header_param(\n            \"X-Request-Id\".to_string(),\n            _internal_style::header(&r#x_request_id, false)?,"
+    ));
+    assert!(api.contains(".with_cookie_params(_internal_style::cookie_pairs(\n            \"session\","));
+}
+
+#[test]
+fn path_item_parameters_are_inherited() {
+    let client = generate("operations_inherited", OPERATIONS, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains(
+        "pub fn r#check_pet(\n        &self,\n        r#pet_id: String,\n        r#verbose: Option<bool>,\n        r#x_request_id: Option<String>,\n    )"
+    ));
+}
+
+#[test]
+fn operation_parameters_override_path_item_ones() {
+    let client = generate("operations_override", OPERATIONS, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains(
+        "pub fn r#show_pet(\n        &self,\n        r#pet_id: String,\n        r#verbose: bool,\n"
+    ));
+    assert_eq!(
+        api.matches("\"verbose\",\n            &r#verbose,").count(),
+        4
+    );
+}
+
+#[test]
+fn optional_parameters_are_taken_as_options() {
+    let client = generate("operations_optional", OPERATIONS, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains("r#session: Option<String>,"));
+    assert!(api.contains("r#x_request_id: Option<String>,"));
+    assert!(!api.contains(".with_header_param("));
+}
+
+#[test]
+fn only_the_runtime_the_operations_call_is_generated() {
+    let client = generate("operations_runtime", OPERATIONS, Options::default()).unwrap();
+    let apis = client.file("apis/mod.rs");
+
+    assert!(client.exists("apis/binary.rs"));
+    assert!(apis.contains("pub use self::binary::ByteStream;"));
+    for module in &["multipart", "links", "pagination", "oauth"] {
+        assert!(!client.exists(&format!("apis/{}.rs", module)));
+        assert!(!apis.contains(&format!("mod {};", module)));
+    }
+    let request = client.file("apis/request.rs");
+    assert!(!request.contains("fn with_multipart("));
+    assert!(!request.contains("fn with_form_params("));
+}
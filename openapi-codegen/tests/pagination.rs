@@ -0,0 +1,78 @@
+mod common;
+
+use common::generate;
+use openapi_codegen::Options;
+
+const PAGINATION: &str = include_str!("specs/pagination.yaml");
+
+#[test]
+fn a_cursor_header_leaves_the_operation_unchanged() {
+    let client = generate("pagination_header", PAGINATION, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains(") -> Result<Vec<Pet>, super::Error<ListPetsError>> {"));
+    assert!(!api.contains("ListPetsHeaders"));
+    assert!(api.contains("let (response, headers) = self.r#list_pets_with_headers("));
+    assert!(api.contains("_internal_pagination::header_cursor(&headers, \"x-next\")?;"));
+}
+
+#[test]
+fn a_link_header_leaves_the_declared_headers_unchanged() {
+    let client = generate("pagination_link", PAGINATION, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains(
+        ") -> Result<super::Response<Vec<String>, ListOwnersHeaders>, super::Error<ListOwnersError>> {"
+    ));
+    assert!(!api.contains("r#link: Option<String>"));
+    assert!(api.contains("let body = response.body;"));
+    assert!(api.contains("_internal_pagination::link_param(&headers, \"page\")?;"));
+}
+
+#[test]
+fn a_body_cursor_is_read_before_the_items() {
+    let client = generate("pagination_body", PAGINATION, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains(
+        "let next = _internal_pagination::cursor(&body, \"/next\")?;\n            let items: Vec<String> = _internal_pagination::items(&body, \"/toys\")?;"
+    ));
+}
+
+#[test]
+fn offsets_stop_short_of_the_limit() {
+    let client = generate("pagination_offset", PAGINATION, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains(
+        "pub fn r#list_vets_pages<'c>(\n        &'c self,\n        r#limit: Option<i32>,\n    )"
+    ));
+    assert!(api.contains("_internal_pagination::next_offset(cursor.unwrap_or(0), items.len(), _internal_pagination::number(&r#limit));"));
+}
+
+#[test]
+fn params_structs_are_given_the_cursor() {
+    let options = Options {
+        params_structs: true,
+        ..Options::default()
+    };
+    let client = generate("pagination_params", PAGINATION, options).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains("Some(value) => params.clone().with_cursor(value),"));
+    assert!(api.contains("_internal_pagination::number(&params.r#limit)"));
+}
+
+#[test]
+fn a_required_cursor_is_an_error() {
+    let spec = PAGINATION.replacen(
+        "        - name: cursor\n          in: query\n",
+        "        - name: cursor\n          in: query\n          required: true\n",
+        1,
+    );
+    let error = generate("pagination_required", &spec, Options::default())
+        .err()
+        .unwrap();
+
+    assert!(error.to_string().contains("must be optional"), "{}", error);
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use common::generate;
+use openapi_codegen::Options;
+
+const PARAMETERS: &str = include_str!("specs/parameters.yaml");
+
+#[test]
+fn parameters_keep_an_explicit_explode() {
+    let client = generate("parameters_explode", PARAMETERS, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api
+        .contains("_internal_style::path(\"ids\", &r#ids, _internal_style::Style::Label, true)?"));
+    assert!(api.contains(
+        "\"tags\",\n            &r#tags,\n            _internal_style::Style::Form,\n            false,"
+    ));
+    assert!(api.contains("_internal_style::header(&r#x_trace, true)?"));
+}
+
+#[test]
+fn form_style_parameters_explode_by_default() {
+    let client = generate("parameters_default_explode", PARAMETERS, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains(
+        "\"sort\",\n            &r#sort,\n            _internal_style::Style::Form,\n            true,"
+    ));
+    assert!(api.contains(
+        "\"session\",\n            &r#session,\n            _internal_style::Style::Form,\n            true,"
+    ));
+}
+
+#[test]
+fn path_item_parameters_keep_an_explicit_explode() {
+    let client = generate("parameters_path_item", PARAMETERS, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains(
+        "\"fields\",\n            &r#fields,\n            _internal_style::Style::Form,\n            false,"
+    ));
+}
+
+#[test]
+fn schema_defaults_are_documented() {
+    let client = generate("parameters_defaults", PARAMETERS, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains("/// `sort` defaults to `\"name\"` when `None`"));
+    assert!(api.contains("/// `limit` defaults to `20` when `None`"));
+}
+
+#[test]
+fn schema_defaults_are_documented_on_params_structs() {
+    let options = Options {
+        params_structs: true,
+        ..Options::default()
+    };
+    let client = generate("parameters_defaults_params", PARAMETERS, options).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains("/// Defaults to `20` when unset\n    pub fn with_limit("));
+}
+
+#[test]
+fn referenced_parameters_are_resolved() {
+    let client = generate("parameters_references", PARAMETERS, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains(
+        "pub fn r#get_pet(\n        &self,\n        r#id: i64,\n        r#verbose: Option<Vec<String>>,\n    )"
+    ));
+    assert!(api.contains(
+        "\"verbose\",\n            &r#verbose,\n            _internal_style::Style::Form,\n            false,"
+    ));
+}
+
+#[test]
+fn a_missing_parameter_is_an_error() {
+    let spec = PARAMETERS.replace("#/components/parameters/Id", "#/components/parameters/Key");
+    let error = generate("parameters_missing", &spec, Options::default())
+        .err()
+        .unwrap();
+
+    assert!(
+        error
+            .to_string()
+            .contains("#/components/parameters/Key is not a parameter of the spec"),
+        "{}",
+        error
+    );
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use common::generate;
+use openapi_codegen::Options;
+
+const PATCH: &str = include_str!("specs/patch.yaml");
+
+#[test]
+fn merge_patch_bodies_take_the_patch_type() {
+    let client = generate("patch_merge", PATCH, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains("r#body: PetPatch,"));
+    assert!(api.contains(".with_content_type(\"application/merge-patch+json\".to_string())"));
+    assert!(api.contains(".with_body_param(r#body)"));
+}
+
+#[test]
+fn json_patch_bodies_are_converted_into_operations() {
+    let client = generate("patch_json", PATCH, Options::default()).unwrap();
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(api.contains(".with_content_type(\"application/json-patch+json\".to_string())"));
+    assert!(api.contains(".with_body_param(r#body.into_json_patch()?)"));
+}
+
+#[test]
+fn only_patched_models_get_a_patch_type() {
+    let client = generate("patch_models", PATCH, Options::default()).unwrap();
+    let models = client.file("models/mod.rs");
+
+    assert!(models.contains("pub use self::pet::r#PetPatch;"));
+    assert!(!models.contains("OwnerPatch"));
+    assert!(client.exists("models/patch.rs"));
+
+    let pet = client.file("models/pet.rs");
+    assert!(pet.contains("pub fn apply(&mut self, patch: r#PetPatch)"));
+    assert!(pet.contains("pub fn diff(old: &r#Pet, new: &r#Pet) -> Self"));
+    assert!(pet.contains("path: \"/tag\".to_string(),"));
+}
+
+#[test]
+fn a_spec_without_patch_bodies_has_no_patch_types() {
+    let spec = PATCH.replace("application/merge-patch+json", "application/json");
+    let spec = spec.replace("application/json-patch+json", "application/json");
+    let client = generate("patch_none", &spec, Options::default()).unwrap();
+
+    assert!(!client.exists("models/patch.rs"));
+    assert!(!client.file("models/mod.rs").contains("Patch"));
+}
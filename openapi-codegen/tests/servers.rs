@@ -0,0 +1,55 @@
+mod common;
+
+use common::generate;
+use openapi_codegen::Options;
+
+const SERVERS: &str = include_str!("specs/servers.yaml");
+
+#[test]
+fn servers_become_variants_with_their_variables() {
+    let client = generate("servers_variants", SERVERS, Options::default()).unwrap();
+    let servers = client.file("apis/servers.rs");
+
+    assert!(servers.contains("Scheme {"));
+    assert!(servers.contains("pub fn r#local() -> Self {"));
+    assert!(servers.contains("impl Default for Configuration {"));
+    assert!(client
+        .file("apis/client.rs")
+        .contains("impl Default for Client {"));
+}
+
+#[test]
+fn operations_are_pointed_at_their_own_servers() {
+    let client = generate("servers_operations", SERVERS, Options::default()).unwrap();
+    let servers = client.file("apis/servers.rs");
+    let api = client.file("apis/untagged_api.rs");
+
+    assert!(servers.contains("`https://files.example.com`"));
+    assert!(servers.contains("`https://uploads.example.com`"));
+    assert_eq!(api.matches(".with_server(").count(), 2, "{}", api);
+}
+
+#[test]
+fn a_spec_without_servers_has_no_default_configuration() {
+    let spec = SERVERS.replacen("servers:", "x-servers:", 1);
+    let client = generate("servers_none", &spec, Options::default()).unwrap();
+    let servers = client.file("apis/servers.rs");
+
+    assert!(!servers.contains("impl Default"), "{}", servers);
+    assert!(servers.contains("None"));
+    assert!(!client.file("apis/client.rs").contains("impl Default"));
+}
+
+#[test]
+fn a_spec_without_any_servers_has_an_empty_server_enum() {
+    let spec = SERVERS
+        .lines()
+        .take_while(|line| !line.starts_with("  /files:"))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .replacen("servers:", "x-servers:", 1);
+    let client = generate("servers_empty", &spec, Options::default()).unwrap();
+    let servers = client.file("apis/servers.rs");
+
+    assert!(servers.contains("match *self {}"), "{}", servers);
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use common::generate;
+use openapi_codegen::Options;
+
+const PARAMETERS: &str = include_str!("specs/parameters.yaml");
+
+const SIGNED: &str = r#"openapi: "3.0.0"
+info:
+  version: 1.0.0
+  title: Signed
+security:
+  - signed: []
+paths:
+  /orders:
+    post:
+      operationId: createOrder
+      responses:
+        '204':
+          description: Ordered
+components:
+  securitySchemes:
+    signed:
+      type: apiKey
+      in: header
+      name: X-Signature
+      x-signature:
+        algorithm: hmac-sha256
+"#;
+
+#[test]
+fn signers_are_left_out_without_a_signed_scheme() {
+    let client = generate("signing_unused", PARAMETERS, Options::default()).unwrap();
+
+    assert!(!client.exists("apis/signing.rs"));
+    assert!(!client.file("apis/mod.rs").contains("pub mod signing;"));
+    assert!(!client.file("apis/credentials.rs").contains("signing"));
+}
+
+#[test]
+fn signed_schemes_bring_the_signers() {
+    let client = generate("signing_used", SIGNED, Options::default()).unwrap();
+
+    assert!(client.exists("apis/signing.rs"));
+    assert!(client.file("apis/mod.rs").contains("pub mod signing;\n"));
+    assert!(client
+        .file("apis/credentials.rs")
+        .contains("Signature::new(super::signing::HmacSha256::new(key_id, secret)"));
+}
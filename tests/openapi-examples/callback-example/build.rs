@@ -4,7 +4,7 @@ fn main() {
     openapi_codegen::client("openapi.yaml", "src/callback_example", true).unwrap();
 
     Command::new("docker")
-            .args(&["build", "-t=test-apisprout", "."])
+            .args(["build", "-t=test-apisprout", "."])
             .output()
             .expect("failed to execute process");
 }
@@ -0,0 +1,46 @@
+use openapi_codegen::{client_with_options, Grouping, Options, TagPlacement};
+
+/// Generates a client for every option set, so each is built and tested
+fn main() {
+    let public = Options {
+        public_fields: true,
+        accessors: false,
+        non_exhaustive: true,
+        ..Options::default()
+    };
+    let borrowed = Options {
+        borrowed_models: true,
+        ..Options::default()
+    };
+    let params = Options {
+        params_structs: true,
+        ..Options::default()
+    };
+    let nested = Options {
+        grouping: Grouping::NestedTag,
+        tag_placement: TagPlacement::Primary,
+        ..Options::default()
+    };
+    let segment = Options {
+        grouping: Grouping::PathSegment,
+        ..Options::default()
+    };
+    let flat = Options {
+        grouping: Grouping::Flat,
+        ..Options::default()
+    };
+
+    for (module, options) in [
+        ("default", Options::default()),
+        ("public", public),
+        ("borrowed", borrowed),
+        ("params", params),
+        ("nested", nested),
+        ("segment", segment),
+        ("flat", flat),
+    ]
+    .iter()
+    {
+        client_with_options("openapi.yaml", &format!("src/{}", module), options.clone()).unwrap();
+    }
+}
@@ -0,0 +1,11 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate serde_derive;
+
+pub mod borrowed;
+pub mod default;
+pub mod flat;
+pub mod nested;
+pub mod params;
+pub mod public;
+pub mod segment;
@@ -0,0 +1,70 @@
+mod common;
+
+use std::io::Cursor;
+
+use features_example::default::apis::configuration::Configuration;
+use features_example::default::apis::{Binary, FilePart, PetsApiClient, UploadPhotoForm};
+
+#[test]
+fn multipart_forms_stream_a_part_per_field() {
+    let (url, requests) = common::serve(vec![common::status(204)]);
+    let form = UploadPhotoForm {
+        photo: FilePart::reader(Cursor::new(b"PNG".to_vec())).with_file_name("rex.png"),
+        caption: Some("Rex".into()),
+    };
+
+    PetsApiClient::new(Configuration::new(url))
+        .upload_photo(1.into(), form)
+        .unwrap();
+    let request = requests.recv().unwrap();
+    let boundary = request
+        .lines()
+        .find_map(|line| line.strip_prefix("content-type: multipart/form-data; boundary="))
+        .unwrap();
+    let body = request
+        .split("\r\n\r\n")
+        .skip(1)
+        .collect::<Vec<_>>()
+        .join("\r\n\r\n");
+    assert_eq!(
+        body,
+        format!(
+            "--{0}\r\nContent-Disposition: form-data; name=\"caption\"\r\n\
+             Content-Type: text/plain\r\n\r\nRex\r\n\
+             --{0}\r\nContent-Disposition: form-data; name=\"photo\"; filename=\"rex.png\"\r\n\
+             Content-Type: image/png\r\n\r\nPNG\r\n\
+             --{0}--\r\n",
+            boundary
+        )
+    );
+}
+
+#[test]
+fn binary_bodies_are_sent_and_streamed_back() {
+    let photo = "HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nContent-Length: 3\r\n\
+        Connection: close\r\n\r\nPNG";
+    let text = "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 8\r\n\
+        Connection: close\r\n\r\nA dog\u{2026}";
+    let (url, requests) = common::serve(vec![photo.to_owned(), text.to_owned(), text.to_owned()]);
+    let client = PetsApiClient::new(Configuration::new(url));
+
+    let mut stream = client.get_photo(1.into()).unwrap();
+    assert_eq!(stream.content_type(), Some("image/png"));
+    let mut copy = Vec::new();
+    assert_eq!(stream.copy_to(&mut copy).unwrap(), 3);
+    assert_eq!(copy, b"PNG");
+    requests.recv().unwrap();
+
+    let description = client
+        .replace_photo(1.into(), Binary::bytes(b"JPG".to_vec()))
+        .unwrap();
+    assert_eq!(description, "A dog\u{2026}");
+    let request = requests.recv().unwrap();
+    assert!(request.contains("content-type: application/octet-stream\r\n"));
+    assert!(request.ends_with("\r\n\r\nJPG"));
+
+    client
+        .replace_photo(1.into(), Binary::reader(Cursor::new(b"GIF".to_vec())))
+        .unwrap();
+    assert!(requests.recv().unwrap().ends_with("\r\n\r\nGIF"));
+}
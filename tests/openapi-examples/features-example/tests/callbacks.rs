@@ -0,0 +1,135 @@
+use features_example::{borrowed, default};
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+
+struct Events(Mutex<Sender<String>>);
+
+impl default::callbacks::OnEventHandler for Events {
+    fn post(
+        &self,
+        _: &hyper::http::request::Parts,
+        body: default::models::Event,
+    ) -> Result<default::callbacks::OnEventPostResponse, failure::Error> {
+        self.0.lock().unwrap().send(body.id().to_owned())?;
+        Ok(default::callbacks::OnEventPostResponse::Status200(
+            default::models::Receipt::new(body.id().to_owned()),
+        ))
+    }
+}
+
+impl borrowed::callbacks::OnEventHandler for Events {
+    fn post(
+        &self,
+        _: &hyper::http::request::Parts,
+        body: borrowed::models::Event<'static>,
+    ) -> Result<borrowed::callbacks::OnEventPostResponse, failure::Error> {
+        self.0.lock().unwrap().send(body.id().to_owned())?;
+        Ok(borrowed::callbacks::OnEventPostResponse::Status200(
+            borrowed::models::Receipt::new(body.id().to_owned().into()),
+        ))
+    }
+}
+
+struct Pings(Mutex<Sender<String>>);
+
+impl default::callbacks::OnPingHandler for Pings {
+    fn post(
+        &self,
+        _: &hyper::http::request::Parts,
+        body: Option<default::models::Event>,
+    ) -> Result<default::callbacks::OnPingPostResponse, failure::Error> {
+        let id = body.map(|body| body.id().to_owned()).unwrap_or_default();
+        self.0.lock().unwrap().send(id)?;
+        Ok(default::callbacks::OnPingPostResponse::Status204)
+    }
+}
+
+impl borrowed::callbacks::OnPingHandler for Pings {
+    fn post(
+        &self,
+        _: &hyper::http::request::Parts,
+        body: Option<borrowed::models::Event<'static>>,
+    ) -> Result<borrowed::callbacks::OnPingPostResponse, failure::Error> {
+        let id = body.map(|body| body.id().to_owned()).unwrap_or_default();
+        self.0.lock().unwrap().send(id)?;
+        Ok(borrowed::callbacks::OnPingPostResponse::Status204)
+    }
+}
+
+/// Sends an event to a router serving on `address`, giving the status and body of its answer
+fn send(address: std::net::SocketAddr, body: &str) -> (u16, String) {
+    let mut response = reqwest::Client::new()
+        .post(&format!("http://{}/events", address))
+        .header("Content-Type", "application/json")
+        .body(body.to_owned())
+        .send()
+        .unwrap();
+    (response.status().as_u16(), response.text().unwrap())
+}
+
+#[test]
+fn callbacks_are_handed_to_their_handler() {
+    let (sender, events) = mpsc::channel();
+    let address = default::callbacks::Router::new()
+        .with_on_event(Events(Mutex::new(sender)))
+        .spawn()
+        .unwrap();
+
+    let (status, body) = send(address, r#"{"id":"created"}"#);
+    assert_eq!((status, body.as_str()), (200, r#"{"event":"created"}"#));
+    assert_eq!(events.recv().unwrap(), "created");
+}
+
+#[test]
+fn borrowed_models_are_handed_over_owned() {
+    let (sender, events) = mpsc::channel();
+    let address = borrowed::callbacks::Router::new()
+        .with_on_event(Events(Mutex::new(sender)))
+        .spawn()
+        .unwrap();
+
+    let (status, body) = send(address, r#"{"id":"created","kind":"pet"}"#);
+    assert_eq!((status, body.as_str()), (200, r#"{"event":"created"}"#));
+    assert_eq!(events.recv().unwrap(), "created");
+}
+
+#[test]
+fn malformed_bodies_are_rejected() {
+    let (sender, _events) = mpsc::channel();
+    let address = borrowed::callbacks::Router::new()
+        .with_on_event(Events(Mutex::new(sender)))
+        .spawn()
+        .unwrap();
+
+    assert_eq!(send(address, r#"{"kind":"pet"}"#).0, 400);
+}
+
+#[test]
+fn callbacks_which_cannot_be_told_apart_are_served_by_a_router_each() {
+    let (event_sender, events) = mpsc::channel();
+    let (ping_sender, pings) = mpsc::channel();
+    let event_address = default::callbacks::Router::new()
+        .with_on_event(Events(Mutex::new(event_sender.clone())))
+        .spawn()
+        .unwrap();
+    let ping_address = default::callbacks::Router::new()
+        .with_on_ping(Pings(Mutex::new(ping_sender.clone())))
+        .spawn()
+        .unwrap();
+
+    assert_eq!(send(event_address, r#"{"id":"event"}"#).0, 200);
+    assert_eq!(send(ping_address, r#"{"id":"ping"}"#).0, 204);
+    assert_eq!(send(ping_address, "").0, 204);
+    assert_eq!(events.recv().unwrap(), "event");
+    assert_eq!(pings.recv().unwrap(), "ping");
+    assert_eq!(pings.recv().unwrap(), "");
+
+    // Within one router, the first handler routed answers
+    let address = borrowed::callbacks::Router::new()
+        .with_on_ping(Pings(Mutex::new(ping_sender)))
+        .with_on_event(Events(Mutex::new(event_sender)))
+        .spawn()
+        .unwrap();
+    assert_eq!(send(address, r#"{"id":"first"}"#).0, 204);
+    assert_eq!(pings.recv().unwrap(), "first");
+}
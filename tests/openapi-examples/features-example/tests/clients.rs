@@ -0,0 +1,44 @@
+mod common;
+
+use features_example::{flat, nested, segment};
+
+const PET: &str = "{\"name\":\"Rex\"}";
+
+#[test]
+fn nested_tags_give_nested_clients() {
+    let (url, requests) = common::serve(vec![common::status(204), common::json(200, PET)]);
+    let client = nested::apis::Client::new(nested::apis::configuration::Configuration::new(url));
+
+    let users: &nested::apis::admin::UsersApiClient = client.admin_users();
+    users
+        .login(nested::apis::admin::LoginForm {
+            username: "alice".into(),
+            password: "open sesame!".into(),
+            ..Default::default()
+        })
+        .unwrap();
+    assert!(requests.recv().unwrap().starts_with("POST /login "));
+    client.pets().get_pet(1.into()).unwrap();
+    assert!(requests.recv().unwrap().starts_with("GET /pets/1 "));
+}
+
+#[test]
+fn path_segments_give_a_client_each() {
+    let (url, requests) = common::serve(vec![common::json(200, PET), common::status(204)]);
+    let client = segment::apis::Client::new(segment::apis::configuration::Configuration::new(url));
+
+    client.pets().get_pet(1.into()).unwrap();
+    assert!(requests.recv().unwrap().starts_with("GET /pets/1 "));
+    client.health().get_health().unwrap();
+    assert!(requests.recv().unwrap().starts_with("GET /2.0/health "));
+}
+
+#[test]
+fn a_flat_client_is_called_directly() {
+    let (url, requests) = common::serve(vec![common::json(200, PET)]);
+    let client = flat::apis::Client::new(flat::apis::configuration::Configuration::new(url));
+
+    let pet = client.get_pet(1.into()).unwrap();
+    assert_eq!(pet.name(), "Rex");
+    assert!(requests.recv().unwrap().starts_with("GET /pets/1 "));
+}
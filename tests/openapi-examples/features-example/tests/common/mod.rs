@@ -0,0 +1,82 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+/// A local server answering each request with the next of `responses`, and handing the text
+/// of every request it reads to the receiver. Returns the server's base URL.
+pub fn serve(responses: Vec<String>) -> (String, Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request = String::new();
+            let mut length = 0;
+            let mut chunked = false;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let lowercase = line.to_lowercase();
+                if let Some(value) = lowercase.strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                chunked |= lowercase.trim() == "transfer-encoding: chunked";
+                request.push_str(&line);
+                if line == "\r\n" || line.is_empty() {
+                    break;
+                }
+            }
+            let body = if chunked {
+                read_chunks(&mut reader)
+            } else {
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                body
+            };
+            request.push_str(&String::from_utf8_lossy(&body));
+
+            stream.write_all(response.as_bytes()).unwrap();
+            sender.send(request).unwrap();
+        }
+    });
+    (url, receiver)
+}
+
+/// Reads a body sent with `Transfer-Encoding: chunked`, as streamed bodies are
+fn read_chunks<R: BufRead>(reader: &mut R) -> Vec<u8> {
+    let mut body = Vec::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let size = usize::from_str_radix(line.trim(), 16).unwrap();
+        let mut chunk = vec![0; size + 2];
+        reader.read_exact(&mut chunk).unwrap();
+        if size == 0 {
+            return body;
+        }
+        body.extend_from_slice(&chunk[..size]);
+    }
+}
+
+/// A JSON response
+#[allow(dead_code)]
+pub fn json(status: u16, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {} OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+/// A response without a body
+#[allow(dead_code)]
+pub fn status(status: u16) -> String {
+    format!(
+        "HTTP/1.1 {} Status\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        status
+    )
+}
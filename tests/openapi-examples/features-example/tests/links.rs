@@ -0,0 +1,20 @@
+mod common;
+
+use features_example::default::apis::configuration::Configuration;
+use features_example::default::apis::OwnersApiClient;
+use features_example::default::models::Pet;
+
+#[test]
+fn links_fill_in_parameters_from_the_body() {
+    let (url, requests) = common::serve(vec![common::json(200, "{\"name\":\"Ann\"}")]);
+    let pet = Pet::new("Rex".into()).with_owner_id(7);
+
+    let owner = OwnersApiClient::new(Configuration::new(url))
+        .follow_owner(&pet)
+        .unwrap();
+    assert_eq!(owner.name(), "Ann");
+    assert!(requests
+        .recv()
+        .unwrap()
+        .starts_with("GET /owners/7?fields=full "));
+}
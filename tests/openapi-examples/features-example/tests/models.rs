@@ -0,0 +1,110 @@
+mod common;
+
+use features_example::{borrowed, default, public};
+
+const PET: &str = r#"{"name":"Rex","kind":"dog","ownerId":7}"#;
+
+#[test]
+fn models_are_read_through_their_accessors() {
+    let (url, requests) = common::serve(vec![common::json(200, PET)]);
+    let configuration = default::apis::configuration::Configuration::new(url);
+
+    let pet = default::apis::PetsApiClient::new(configuration)
+        .get_pet(1.into())
+        .unwrap();
+    assert!(requests.recv().unwrap().starts_with("GET /pets/1 "));
+    assert_eq!(pet.name(), "Rex");
+    assert_eq!(pet.kind(), Some(&default::models::PetKind::Dog));
+    assert_eq!(pet.owner_id(), Some(&7));
+}
+
+#[test]
+fn borrowed_models_are_read_into_the_buffer() {
+    let (url, _) = common::serve(vec![common::json(200, PET)]);
+    let configuration = borrowed::apis::configuration::Configuration::new(url);
+    let mut buffer = Vec::new();
+
+    let pet = borrowed::apis::PetsApiClient::new(configuration)
+        .get_pet(1.into(), &mut buffer)
+        .unwrap();
+    assert_eq!(pet.name(), "Rex");
+    // The owned form outlives the buffer
+    let pet: borrowed::models::Pet<'static> = pet.into_owned();
+    drop(buffer);
+    assert_eq!(pet.owner_id(), Some(&7));
+}
+
+#[test]
+fn public_fields_can_be_destructured_and_set() {
+    let (url, _) = common::serve(vec![common::json(200, PET)]);
+    let configuration = public::apis::configuration::Configuration::new(url);
+
+    let public::models::Pet { name, kind, .. } = public::apis::PetsApiClient::new(configuration)
+        .get_pet(1.into())
+        .unwrap();
+    assert_eq!(name, "Rex");
+    assert_eq!(kind, Some(public::models::PetKind::Dog));
+
+    // Structs are `#[non_exhaustive]`, so are built from their default
+    let mut pet = public::models::Pet::default();
+    pet.name = "Tom".into();
+    pet.kind = Some(public::models::PetKind::Cat);
+    assert_eq!(
+        serde_json::to_string(&pet).unwrap(),
+        r#"{"kind":"cat","name":"Tom"}"#
+    );
+}
+
+#[test]
+fn patches_send_only_what_changed() {
+    let (url, requests) = common::serve(vec![common::status(204)]);
+    let configuration = default::apis::configuration::Configuration::new(url);
+    let pet = default::models::Pet::new("Rex".into()).with_owner_id(7);
+    let mut renamed = pet.clone().with_name("Max".into());
+    renamed.reset_owner_id();
+
+    let patch = default::models::PetPatch::diff(&pet, &renamed);
+    default::apis::PetsApiClient::new(configuration)
+        .update_pet(1.into(), patch.clone())
+        .unwrap();
+    let request = requests.recv().unwrap();
+    assert!(request.starts_with("PATCH /pets/1 "));
+    assert!(request.contains("content-type: application/merge-patch+json"));
+    assert!(request.ends_with(r#"{"ownerId":null,"name":"Max"}"#));
+
+    let mut patched = pet;
+    patched.apply(patch);
+    assert_eq!(patched, renamed);
+}
+
+#[test]
+fn enums_are_parsed_and_sent_as_their_names() {
+    let (url, requests) = common::serve(vec![common::json(200, &format!("[{}]", PET))]);
+    let configuration = default::apis::configuration::Configuration::new(url);
+    let kind = "dog".parse::<default::models::PetKind>().unwrap();
+
+    let pets = default::apis::PetsApiClient::new(configuration)
+        .list_pets(kind, None)
+        .unwrap();
+    assert!(requests.recv().unwrap().starts_with("GET /pets?kind=dog "));
+    assert_eq!(pets[0].kind().map(ToString::to_string), Some("dog".into()));
+    assert!("bird".parse::<default::models::PetKind>().is_err());
+}
+
+#[test]
+fn newtypes_convert_to_and_from_what_they_wrap() {
+    let (url, requests) = common::serve(vec![common::json(200, &format!("[{}]", PET))]);
+    let configuration = default::apis::configuration::Configuration::new(url);
+    let id = "7".parse::<default::models::PetId>().unwrap();
+    assert_eq!(*id, 7);
+    assert_eq!(id.to_string(), "7");
+    assert_eq!(i64::from(id), 7);
+
+    let pets = default::apis::PetsApiClient::new(configuration)
+        .list_pets(default::models::PetKind::Dog, None)
+        .unwrap();
+    requests.recv().unwrap();
+    assert_eq!(pets.len(), 1);
+    let names = pets.into_iter().map(|pet| pet.name().to_owned());
+    assert_eq!(names.collect::<Vec<String>>(), vec!["Rex"]);
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use features_example::default::apis::configuration::Configuration;
+use features_example::default::apis::{
+    AdminUsersApiClient, ApiError, Error, LoginForm, PetExistsError, PetsApiClient,
+    ReplacePetError, ReplacePetResponse, UntaggedApiClient,
+};
+use features_example::default::models::{Pet, PetKind};
+
+#[test]
+fn head_operations_return_the_headers() {
+    let (url, requests) = common::serve(vec![common::status(200), common::status(404)]);
+    let client = PetsApiClient::new(Configuration::new(url));
+
+    let headers = client.pet_exists(1.into()).unwrap();
+    assert!(requests.recv().unwrap().starts_with("HEAD /pets/1 "));
+    assert_eq!(headers["content-length"], "0");
+
+    match client.pet_exists(2.into()) {
+        Err(Error::ApiError(ApiError {
+            content: Some(PetExistsError::Status404),
+            ..
+        })) => {}
+        other => panic!("expected a 404, got {:?}", other),
+    }
+}
+
+#[test]
+fn operations_without_an_id_are_named_after_their_method_and_path() {
+    let (url, requests) = common::serve(vec![common::status(204)]);
+
+    PetsApiClient::new(Configuration::new(url))
+        .delete_pets_by_id(1.into())
+        .unwrap();
+    assert!(requests.recv().unwrap().starts_with("DELETE /pets/1 "));
+}
+
+#[test]
+fn each_documented_status_is_told_apart() {
+    let problem = "{\"title\":\"Changed\"}";
+    let conflict = format!(
+        "HTTP/1.1 409 Conflict\r\nContent-Type: application/problem+json\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        problem.len(),
+        problem
+    );
+    let (url, _requests) = common::serve(vec![
+        common::json(201, "{\"name\":\"Rex\"}"),
+        conflict,
+        common::status(500),
+    ]);
+    let client = PetsApiClient::new(Configuration::new(url));
+    let pet = Pet::new("Rex".into());
+
+    match client.replace_pet(1.into(), pet.clone()).unwrap() {
+        ReplacePetResponse::Status201(created) => assert_eq!(created, pet),
+        other => panic!("expected a 201, got {:?}", other),
+    }
+    match client.replace_pet(1.into(), pet.clone()) {
+        Err(Error::ApiError(ApiError {
+            content: Some(ReplacePetError::Status409(problem)),
+            ..
+        })) => assert_eq!(problem.title(), "Changed"),
+        other => panic!("expected a 409, got {:?}", other),
+    }
+    match client.replace_pet(1.into(), pet) {
+        Err(Error::ApiError(ApiError { content: None, .. })) => {}
+        other => panic!("expected an undocumented status, got {:?}", other),
+    }
+}
+
+#[test]
+fn urlencoded_forms_leave_out_unset_fields() {
+    let (url, requests) = common::serve(vec![common::status(204)]);
+    let form = LoginForm {
+        username: "alice".into(),
+        password: "open sesame!".into(),
+        ..LoginForm::default()
+    };
+
+    AdminUsersApiClient::new(Configuration::new(url))
+        .login(form)
+        .unwrap();
+    let request = requests.recv().unwrap();
+    assert!(request.starts_with("POST /login "));
+    assert!(request.contains("content-type: application/x-www-form-urlencoded"));
+    assert!(request.ends_with("\r\n\r\npassword=open+sesame%21&username=alice"));
+}
+
+#[test]
+fn optional_parameters_are_left_off_unless_set() {
+    let (url, requests) = common::serve(vec![common::json(200, "[]"), common::json(200, "[]")]);
+    let client = PetsApiClient::new(Configuration::new(url));
+
+    client.list_pets(PetKind::Cat, None).unwrap();
+    assert!(requests.recv().unwrap().starts_with("GET /pets?kind=cat "));
+    client.list_pets(PetKind::Cat, Some(2)).unwrap();
+    assert!(requests
+        .recv()
+        .unwrap()
+        .starts_with("GET /pets?kind=cat&limit=2 "));
+}
+
+#[test]
+fn declared_response_headers_are_parsed() {
+    let response = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nX-Version: 1.2.0\r\n\
+        Content-Length: 16\r\nConnection: close\r\n\r\n{\"healthy\":true}";
+    let (url, _) = common::serve(vec![response.to_owned()]);
+
+    let status = UntaggedApiClient::new(Configuration::new(url))
+        .get_status()
+        .unwrap();
+    assert!(status.body.healthy());
+    assert_eq!(status.headers.x_version, "1.2.0");
+    assert_eq!(status.headers.x_uptime, None);
+}
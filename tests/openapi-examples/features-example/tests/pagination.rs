@@ -0,0 +1,100 @@
+mod common;
+
+use features_example::default::apis::configuration::Configuration;
+use features_example::default::apis::UntaggedApiClient;
+use std::sync::mpsc::Receiver;
+
+/// A JSON page, along with the headers pointing at the next one
+fn page(body: &str, headers: &str) -> String {
+    format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        headers,
+        body.len(),
+        body
+    )
+}
+
+fn client(pages: Vec<String>) -> (UntaggedApiClient, Receiver<String>) {
+    let (url, requests) = common::serve(pages);
+    (UntaggedApiClient::new(Configuration::new(url)), requests)
+}
+
+/// The request lines of the next `count` requests the server receives
+fn request_lines(requests: &Receiver<String>, count: usize) -> Vec<String> {
+    requests
+        .iter()
+        .take(count)
+        .map(|request| request.lines().next().unwrap().to_owned())
+        .collect()
+}
+
+#[test]
+fn header_cursors_are_followed() {
+    let (client, requests) = client(vec![page("[1,2]", "x-next: abc\r\n"), page("[3]", "")]);
+
+    let ids = client.list_ids_pages().map(|id| *id.unwrap());
+    assert_eq!(ids.collect::<Vec<i64>>(), vec![1, 2, 3]);
+    assert_eq!(
+        request_lines(&requests, 2),
+        vec!["GET /ids HTTP/1.1", "GET /ids?cursor=abc HTTP/1.1"]
+    );
+}
+
+#[test]
+fn next_links_are_followed() {
+    // Only the cursor parameter is read from the link
+    let next = "link: <http://features.example.com/visits?page=2>; rel=\"next\"\r\n";
+    let (client, requests) = client(vec![
+        page(r#"[{"day":1}]"#, next),
+        page(r#"[{"day":2}]"#, ""),
+    ]);
+
+    let days = client
+        .list_visits_pages()
+        .map(|visit| *visit.unwrap().day());
+    assert_eq!(days.collect::<Vec<i32>>(), vec![1, 2]);
+    assert_eq!(
+        request_lines(&requests, 2),
+        vec!["GET /visits HTTP/1.1", "GET /visits?page=2 HTTP/1.1"]
+    );
+}
+
+#[test]
+fn body_cursors_are_followed() {
+    let (client, requests) = client(vec![
+        page(r#"{"toys":[1,2],"next":7}"#, ""),
+        page(r#"{"toys":[3]}"#, ""),
+    ]);
+
+    let toys = client.list_toys_pages().map(Result::unwrap);
+    assert_eq!(toys.collect::<Vec<i64>>(), vec![1, 2, 3]);
+    assert_eq!(
+        request_lines(&requests, 2),
+        vec!["GET /toys HTTP/1.1", "GET /toys?after=7 HTTP/1.1"]
+    );
+}
+
+#[test]
+fn offsets_move_on_until_a_short_page() {
+    let (client, requests) = client(vec![page("[1,2]", ""), page("[3]", "")]);
+
+    let scores = client.list_scores_pages(Some(2)).map(Result::unwrap);
+    assert_eq!(scores.collect::<Vec<i32>>(), vec![1, 2, 3]);
+    assert_eq!(
+        request_lines(&requests, 2),
+        vec![
+            "GET /scores?offset=0&limit=2 HTTP/1.1",
+            "GET /scores?offset=2&limit=2 HTTP/1.1"
+        ]
+    );
+}
+
+#[test]
+fn an_error_ends_the_pages() {
+    let (client, _) = client(vec![page("[1]", "x-next: abc\r\n"), common::status(500)]);
+
+    let mut ids = client.list_ids_pages();
+    assert_eq!(*ids.next().unwrap().unwrap(), 1);
+    assert!(ids.next().unwrap().is_err());
+    assert!(ids.next().is_none());
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use features_example::default::apis::configuration::Configuration;
+use features_example::default::apis::{OwnersApiClient, UntaggedApiClient};
+use features_example::default::models::Theme;
+use features_example::params;
+
+fn cookies(request: &str) -> &str {
+    request
+        .lines()
+        .find_map(|line| line.strip_prefix("cookie: "))
+        .unwrap_or_default()
+}
+
+#[test]
+fn operations_override_the_parameters_of_their_path() {
+    let (url, requests) = common::serve(vec![common::json(200, "[]")]);
+
+    OwnersApiClient::new(Configuration::new(url))
+        .list_owner_pets(7, "full".into())
+        .unwrap();
+    assert!(requests
+        .recv()
+        .unwrap()
+        .starts_with("GET /owners/7/pets?fields=full "));
+}
+
+#[test]
+fn header_and_cookie_parameters_are_sent() {
+    let (url, requests) = common::serve(vec![common::status(204), common::status(204)]);
+    let client = UntaggedApiClient::new(Configuration::new(url));
+
+    client.get_treats("abc".into(), None).unwrap();
+    let request = requests.recv().unwrap();
+    assert!(request.contains("\r\nx-trace: abc\r\n"));
+    assert_eq!(cookies(&request), "");
+    client.get_treats("abc".into(), Some("s1".into())).unwrap();
+    assert_eq!(cookies(&requests.recv().unwrap()), "session=s1");
+}
+
+#[test]
+fn form_arrays_are_sent_as_percent_encoded_cookies() {
+    let (url, requests) = common::serve(vec![common::status(204)]);
+
+    UntaggedApiClient::new(Configuration::new(url))
+        .get_preferences(Some(vec!["cats".into(), "big dogs".into()]), None)
+        .unwrap();
+    assert_eq!(cookies(&requests.recv().unwrap()), "tags=cats%2Cbig%20dogs");
+}
+
+#[test]
+fn exploded_objects_are_sent_as_a_cookie_each() {
+    let (url, requests) = common::serve(vec![common::status(204)]);
+    let theme = Theme::new()
+        .with_color("dark;blue".into())
+        .with_size("large".into());
+
+    UntaggedApiClient::new(Configuration::new(url))
+        .get_preferences(None, Some(theme))
+        .unwrap();
+    assert_eq!(
+        cookies(&requests.recv().unwrap()),
+        "color=dark%3Bblue; size=large"
+    );
+}
+
+#[test]
+fn parameter_structs_send_what_was_set() {
+    let (url, requests) = common::serve(vec![common::json(200, "[]"), common::status(204)]);
+    let configuration = params::apis::configuration::Configuration::new(url);
+    let params = params::apis::ListPetsParams::new(params::models::PetKind::Dog).with_limit(2);
+
+    params::apis::PetsApiClient::new(configuration.clone())
+        .list_pets(params)
+        .unwrap();
+    assert!(requests
+        .recv()
+        .unwrap()
+        .starts_with("GET /pets?kind=dog&limit=2 "));
+
+    // Operations without required parameters can start from the default
+    params::apis::UntaggedApiClient::new(configuration)
+        .get_preferences(params::apis::GetPreferencesParams::default())
+        .unwrap();
+    assert_eq!(cookies(&requests.recv().unwrap()), "");
+}
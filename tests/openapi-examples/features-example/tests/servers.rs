@@ -0,0 +1,28 @@
+mod common;
+
+use features_example::default::apis::configuration::Configuration;
+use features_example::default::apis::{Server, UntaggedApiClient};
+
+#[test]
+fn a_base_path_of_its_own_takes_every_operation() {
+    let (url, requests) = common::serve(vec![common::status(204)]);
+
+    UntaggedApiClient::new(Configuration::new(url))
+        .get_archive()
+        .unwrap();
+    assert!(requests.recv().unwrap().starts_with("GET /archive "));
+}
+
+#[test]
+fn servers_are_overridden_by_method_name() {
+    let (url, requests) = common::serve(vec![common::status(204)]);
+    let port = url.rsplit(':').next().unwrap().to_owned();
+    let mut configuration = Configuration::default();
+    configuration
+        .servers
+        .insert("get_archive".into(), Server::Local { port });
+
+    UntaggedApiClient::new(configuration).get_archive().unwrap();
+    assert!(requests.recv().unwrap().starts_with("GET /archive "));
+    assert_eq!(Configuration::default().base_path, Server::default().url());
+}
@@ -0,0 +1,3 @@
+fn main() {
+    openapi_codegen::client("openapi.yaml", "src/security_example", false).unwrap();
+}
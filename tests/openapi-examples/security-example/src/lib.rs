@@ -0,0 +1,5 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate serde_derive;
+
+pub mod security_example;
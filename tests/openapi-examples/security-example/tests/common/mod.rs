@@ -0,0 +1,74 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+/// A local server answering each request with the next of `responses`, and handing the text
+/// of every request it reads to the receiver. Returns the server's base URL.
+pub fn serve(responses: Vec<String>) -> (String, Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request = String::new();
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let lowercase = line.to_lowercase();
+                if let Some(value) = lowercase.strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                request.push_str(&line);
+                if line == "\r\n" || line.is_empty() {
+                    break;
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            request.push_str(&String::from_utf8_lossy(&body));
+
+            stream.write_all(response.as_bytes()).unwrap();
+            sender.send(request).unwrap();
+        }
+    });
+    (url, receiver)
+}
+
+/// A token endpoint's answer
+#[allow(dead_code)]
+pub fn token(access_token: &str, refresh_token: Option<&str>, expires_in: u64) -> String {
+    let body = match refresh_token {
+        Some(refresh_token) => format!(
+            r#"{{"access_token":"{}","token_type":"bearer","refresh_token":"{}","expires_in":{}}}"#,
+            access_token, refresh_token, expires_in
+        ),
+        None => format!(
+            r#"{{"access_token":"{}","token_type":"bearer","expires_in":{}}}"#,
+            access_token, expires_in
+        ),
+    };
+    json(200, &body)
+}
+
+/// A JSON response
+#[allow(dead_code)]
+pub fn json(status: u16, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {} OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+/// A response without a body
+pub fn status(status: u16) -> String {
+    format!(
+        "HTTP/1.1 {} Status\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        status
+    )
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use security_example::security_example::apis::client::Client;
+use security_example::security_example::apis::configuration::Configuration;
+use security_example::security_example::apis::credentials::Credentials;
+
+fn client(url: String, credentials: Credentials) -> Client {
+    let mut configuration = Configuration::new(url);
+    configuration.credentials = credentials;
+    Client::new(configuration)
+}
+
+#[test]
+fn optional_authentication_sends_the_credentials_it_has() {
+    let (url, requests) = common::serve(vec![common::status(204), common::status(204)]);
+
+    let client = client(url.clone(), Credentials::new());
+    client.untagged().get_greeting().unwrap();
+    assert!(!requests.recv().unwrap().contains("x-api-key"));
+
+    let client = self::client(url, Credentials::new().with_api_key("secret".into()));
+    client.untagged().get_greeting().unwrap();
+    assert!(requests.recv().unwrap().contains("x-api-key: secret"));
+}
+
+#[test]
+fn the_first_requirement_fully_met_is_sent() {
+    let (url, requests) = common::serve(vec![common::status(204)]);
+    let credentials = Credentials::new()
+        .with_api_key("secret".into())
+        .with_bearer("token".into());
+
+    client(url, credentials).untagged().get_secret().unwrap();
+    let request = requests.recv().unwrap();
+    assert!(!request.contains("x-api-key"));
+    assert!(request.contains("authorization: Bearer token"));
+}
+
+#[test]
+fn operations_fall_back_to_the_top_level_security() {
+    let (url, requests) = common::serve(vec![common::status(204)]);
+
+    client(url, Credentials::new().with_api_key("secret".into()))
+        .untagged()
+        .get_status()
+        .unwrap();
+    assert!(requests.recv().unwrap().contains("x-api-key: secret"));
+}
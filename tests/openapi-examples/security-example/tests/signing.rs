@@ -0,0 +1,106 @@
+mod common;
+
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+use security_example::security_example::apis::client::Client;
+use security_example::security_example::apis::configuration::{Configuration, Signature, Signer};
+use security_example::security_example::apis::credentials::Credentials;
+use security_example::security_example::models::Order;
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// The value of a header in the text of a request
+fn header<'a>(request: &'a str, name: &str) -> Option<&'a str> {
+    request.lines().find_map(|line| {
+        let mut parts = line.splitn(2, ':');
+        match (parts.next(), parts.next()) {
+            (Some(header), Some(value)) if header.eq_ignore_ascii_case(name) => Some(value.trim()),
+            _ => None,
+        }
+    })
+}
+
+#[test]
+fn hmac_sha256_signs_the_canonical_request() {
+    let (url, requests) = common::serve(vec![common::status(204)]);
+    let mut configuration = Configuration::new(url.clone());
+    configuration.credentials =
+        Credentials::new().with_signed_hmac_sha256("key".into(), b"secret".to_vec());
+    let order = Order::new().with_item("ball".into());
+
+    Client::new(configuration)
+        .untagged()
+        .create_order(Some("high".into()), Some(true), order)
+        .unwrap();
+
+    let request = requests.recv().unwrap();
+    let (_, body) = request.split_once("\r\n\r\n").unwrap();
+    let canonical = format!(
+        "POST\n/orders\nexpress=true&priority=high\nhost:{}\ncontent-type:application/json\nhost;content-type\n{}",
+        url.trim_start_matches("http://"),
+        hex(&Sha256::digest(body.as_bytes()))
+    );
+    let mut mac = Hmac::<Sha256>::new_varkey(b"secret").unwrap();
+    mac.input(canonical.as_bytes());
+    assert_eq!(
+        header(&request, "x-signature"),
+        Some(
+            format!(
+                "HMAC-SHA256 KeyId=key, SignedHeaders=host;content-type, Signature={}",
+                hex(&mac.result().code())
+            )
+            .as_str()
+        )
+    );
+}
+
+#[test]
+fn operations_without_the_scheme_are_not_signed() {
+    let (url, requests) = common::serve(vec![common::status(204)]);
+    let mut configuration = Configuration::new(url);
+    configuration.credentials =
+        Credentials::new().with_signed_hmac_sha256("key".into(), b"secret".to_vec());
+
+    Client::new(configuration)
+        .untagged()
+        .get_greeting()
+        .unwrap();
+
+    assert_eq!(header(&requests.recv().unwrap(), "x-signature"), None);
+}
+
+struct Stamp;
+
+impl Signer for Stamp {
+    fn sign(
+        &self,
+        request: &mut reqwest::Request,
+        _body: Option<&[u8]>,
+    ) -> Result<(), failure::Error> {
+        request.headers_mut().insert(
+            "x-stamp",
+            hyper::header::HeaderValue::from_static("stamped"),
+        );
+        Ok(())
+    }
+}
+
+#[test]
+fn the_configuration_signer_signs_every_request() {
+    let (url, requests) = common::serve(vec![common::status(204)]);
+    let mut configuration = Configuration::new(url);
+    configuration.signer = Some(Signature::new(Stamp));
+
+    Client::new(configuration)
+        .untagged()
+        .get_greeting()
+        .unwrap();
+
+    assert_eq!(
+        header(&requests.recv().unwrap(), "x-stamp"),
+        Some("stamped")
+    );
+}